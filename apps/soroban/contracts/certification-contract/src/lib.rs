@@ -1,12 +1,36 @@
 #![no_std]
 use soroban_sdk::contracttype;
 use soroban_sdk::{
-    contract, contracterror, contractimpl, symbol_short, Address, Env, Map, String, Symbol, Vec,
+    contract, contracterror, contractimpl, symbol_short, Address, Env, String, Symbol, Vec,
 };
 
 /// Define the contract data storage keys
 const ADMIN: Symbol = symbol_short!("ADMIN");
-const CERTIFICATES: Symbol = symbol_short!("CERTS");
+const CERT_COUNT: Symbol = symbol_short!("CERT_CNT");
+const CONFIG: Symbol = symbol_short!("CONFIG");
+
+/// Define the event topics
+const RENOUNCED: Symbol = symbol_short!("RENOUNCED");
+
+/// Maximum number of certificate IDs returned by a single page
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Approximate number of ledgers closed per day (one every five seconds)
+const DAY_IN_LEDGERS: u32 = 17_280;
+/// Default remaining TTL below which entries are extended on access
+const DEFAULT_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+/// Default TTL that entries are extended to
+const DEFAULT_TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
+
+/// Define the persistent storage keys, one ledger entry per key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    /// Certificate details by certificate ID
+    Certificate(String),
+    /// Certificate ID by issuance position
+    CertificateIndex(u32),
+}
 
 /// Define the error types for the contract
 #[contracterror]
@@ -21,6 +45,12 @@ pub enum Error {
     CertificateNotFound = 3,
     /// Error when the contract has already been initialized
     AlreadyInitialized = 4,
+    /// Error when a parameter is out of range
+    InvalidParameter = 5,
+    /// Error when a certificate has been renounced by its owner
+    CertificateRenounced = 6,
+    /// Error when a certificate has been revoked
+    CertificateRevoked = 7,
 }
 
 /// Define the certificate details structure
@@ -34,6 +64,30 @@ pub struct CertificateDetails {
     pub metadata_hash: String,
     /// Whether the certificate is valid or revoked
     pub is_valid: bool,
+    /// Whether the owner renounced the certificate, leaving only a tombstone
+    pub is_renounced: bool,
+}
+
+/// Define a page of certificate IDs returned by the paginated listing
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificatePage {
+    /// The certificate IDs on this page, in issuance order
+    pub cert_ids: Vec<String>,
+    /// The position to pass as `start` to fetch the next page, if any
+    pub next_cursor: Option<u32>,
+    /// The total number of certificates issued
+    pub total: u32,
+}
+
+/// Define the TTL configuration used to keep contract data in live state
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    /// Remaining TTL, in ledgers, below which entries are extended on access
+    pub threshold: u32,
+    /// TTL, in ledgers, that entries are extended to
+    pub extend_to: u32,
 }
 
 /// The main contract struct
@@ -59,9 +113,18 @@ impl Contract {
         // Store the admin address
         env.storage().instance().set(&ADMIN, &admin);
 
-        // Initialize an empty certificates map
-        let certificates: Map<String, CertificateDetails> = Map::new(&env);
-        env.storage().instance().set(&CERTIFICATES, &certificates);
+        // Initialize the certificate counter
+        env.storage().instance().set(&CERT_COUNT, &0u32);
+
+        // Store the default TTL configuration
+        env.storage().instance().set(
+            &CONFIG,
+            &TtlConfig {
+                threshold: DEFAULT_TTL_THRESHOLD,
+                extend_to: DEFAULT_TTL_EXTEND_TO,
+            },
+        );
+        Self::extend_instance_ttl(&env);
 
         Ok(())
     }
@@ -88,12 +151,9 @@ impl Contract {
         // Require authorization from admin
         admin.require_auth();
 
-        // Get the certificates map
-        let mut certificates: Map<String, CertificateDetails> =
-            env.storage().instance().get(&CERTIFICATES).unwrap();
-
         // Check if the certificate already exists
-        if certificates.contains_key(cert_id.clone()) {
+        let key = DataKey::Certificate(cert_id.clone());
+        if env.storage().persistent().has(&key) {
             return Err(Error::CertificateAlreadyExists);
         }
 
@@ -102,13 +162,20 @@ impl Contract {
             owner,
             metadata_hash,
             is_valid: true,
+            is_renounced: false,
         };
 
-        // Add the certificate to the map
-        certificates.set(cert_id, certificate);
+        // Store the certificate in its own entry
+        env.storage().persistent().set(&key, &certificate);
+        Self::extend_persistent_ttl(&env, &key);
 
-        // Update the certificates map in storage
-        env.storage().instance().set(&CERTIFICATES, &certificates);
+        // Record the certificate in the issuance index
+        let count: u32 = env.storage().instance().get(&CERT_COUNT).unwrap_or(0);
+        let index_key = DataKey::CertificateIndex(count);
+        env.storage().persistent().set(&index_key, &cert_id);
+        Self::extend_persistent_ttl(&env, &index_key);
+        env.storage().instance().set(&CERT_COUNT, &(count + 1));
+        Self::extend_instance_ttl(&env);
 
         Ok(())
     }
@@ -128,26 +195,68 @@ impl Contract {
         // Require authorization from admin
         admin.require_auth();
 
-        // Get the certificates map
-        let mut certificates: Map<String, CertificateDetails> =
-            env.storage().instance().get(&CERTIFICATES).unwrap();
+        // Get the certificate
+        let key = DataKey::Certificate(cert_id);
+        let mut certificate: CertificateDetails = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::CertificateNotFound)?;
 
-        // Check if the certificate exists
-        if !certificates.contains_key(cert_id.clone()) {
-            return Err(Error::CertificateNotFound);
-        }
+        // Update the certificate validity
+        certificate.is_valid = false;
 
+        // Update the certificate in storage
+        env.storage().persistent().set(&key, &certificate);
+        Self::extend_persistent_ttl(&env, &key);
+        Self::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Renounce a certificate (for owner use)
+    ///
+    /// The metadata hash is cleared and the certificate is kept as a
+    /// tombstone so that its ID cannot be issued again. Revoked certificates
+    /// cannot be renounced, so the revocation stays on record.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn renounce_certificate(env: Env, cert_id: String) -> Result<(), Error> {
         // Get the certificate
-        let mut certificate = certificates.get(cert_id.clone()).unwrap();
+        let key = DataKey::Certificate(cert_id.clone());
+        let mut certificate: CertificateDetails = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::CertificateNotFound)?;
+
+        // Require authorization from the owner
+        certificate.owner.require_auth();
+
+        if certificate.is_renounced {
+            return Err(Error::CertificateRenounced);
+        }
+        if !certificate.is_valid {
+            return Err(Error::CertificateRevoked);
+        }
 
-        // Update the certificate validity
+        // Drop the public metadata and invalidate the certificate
+        certificate.metadata_hash = String::from_str(&env, "");
         certificate.is_valid = false;
+        certificate.is_renounced = true;
 
-        // Update the certificate in the map
-        certificates.set(cert_id, certificate);
+        // Update the certificate in storage
+        env.storage().persistent().set(&key, &certificate);
+        Self::extend_persistent_ttl(&env, &key);
+        Self::extend_instance_ttl(&env);
 
-        // Update the certificates map in storage
-        env.storage().instance().set(&CERTIFICATES, &certificates);
+        env.events()
+            .publish((RENOUNCED, cert_id), certificate.owner.clone());
 
         Ok(())
     }
@@ -161,17 +270,91 @@ impl Contract {
     /// # Returns
     /// * `Result<CertificateDetails, Error>` - The certificate details or an error
     pub fn get_certificate_details(env: Env, cert_id: String) -> Result<CertificateDetails, Error> {
-        // Get the certificates map
-        let certificates: Map<String, CertificateDetails> =
-            env.storage().instance().get(&CERTIFICATES).unwrap();
+        // Return the certificate details if the certificate exists
+        let key = DataKey::Certificate(cert_id);
+        let certificate = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::CertificateNotFound)?;
+
+        // Keep certificates that are still being read in live state
+        Self::extend_persistent_ttl(&env, &key);
+        Self::extend_instance_ttl(&env);
+
+        Ok(certificate)
+    }
 
+    /// Extend the TTL of a certificate to the configured maximum
+    ///
+    /// Anyone, such as the holder or a verifier, can pay to keep a
+    /// certificate in live state.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn extend_certificate_ttl(env: Env, cert_id: String) -> Result<(), Error> {
         // Check if the certificate exists
-        if !certificates.contains_key(cert_id.clone()) {
+        let key = DataKey::Certificate(cert_id);
+        if !env.storage().persistent().has(&key) {
             return Err(Error::CertificateNotFound);
         }
 
-        // Return the certificate details
-        Ok(certificates.get(cert_id).unwrap())
+        // Extend the entry to the full TTL regardless of the threshold
+        let config = Self::ttl_config(&env);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, config.extend_to, config.extend_to);
+        Self::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the TTL configuration
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    ///
+    /// # Returns
+    /// * `TtlConfig` - The current TTL configuration
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        Self::ttl_config(&env)
+    }
+
+    /// Set the TTL configuration (for admin use)
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `threshold` - Remaining TTL, in ledgers, below which entries are extended
+    /// * `extend_to` - TTL, in ledgers, that entries are extended to
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_ttl_config(env: Env, threshold: u32, extend_to: u32) -> Result<(), Error> {
+        // Check if the caller is the admin
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+
+        // Require authorization from admin
+        admin.require_auth();
+
+        // The threshold must be reachable and the target within the network limit
+        if threshold == 0 || threshold > extend_to || extend_to > env.storage().max_ttl() {
+            return Err(Error::InvalidParameter);
+        }
+
+        env.storage().instance().set(
+            &CONFIG,
+            &TtlConfig {
+                threshold,
+                extend_to,
+            },
+        );
+        Self::extend_instance_ttl(&env);
+
+        Ok(())
     }
 
     /// Verify a certificate by checking its existence and validity
@@ -209,18 +392,91 @@ impl Contract {
         // Require authorization from admin
         admin.require_auth();
 
-        // Get the certificates map
-        let certificates: Map<String, CertificateDetails> =
-            env.storage().instance().get(&CERTIFICATES).unwrap();
+        // Collect the certificate IDs from the issuance index
+        let count: u32 = env.storage().instance().get(&CERT_COUNT).unwrap_or(0);
+        Self::certificate_ids(&env, 0, count)
+    }
+
+    /// List certificate IDs one page at a time (for admin use)
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `start` - The issuance position to start from (0 for the first page)
+    /// * `limit` - The maximum number of IDs to return, capped at 100
+    ///
+    /// # Returns
+    /// * `Result<CertificatePage, Error>` - The page of IDs, the next cursor and the total count
+    pub fn list_certificates_page(
+        env: Env,
+        start: u32,
+        limit: u32,
+    ) -> Result<CertificatePage, Error> {
+        // Check if the caller is the admin
+        let admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+
+        // Require authorization from admin
+        admin.require_auth();
+
+        // Validate the page size
+        if limit == 0 {
+            return Err(Error::InvalidParameter);
+        }
+        let limit = limit.min(MAX_PAGE_SIZE);
 
-        // Create a vector of certificate IDs
-        let mut cert_ids = Vec::new(&env);
-        for (id, _) in certificates.iter() {
-            cert_ids.push_back(id);
+        // Read the requested window of the issuance index
+        let total: u32 = env.storage().instance().get(&CERT_COUNT).unwrap_or(0);
+        let end = start.saturating_add(limit).min(total);
+        let cert_ids = Self::certificate_ids(&env, start, end);
+
+        // Point to the next page only if more certificates remain
+        let next_cursor = if end < total { Some(end) } else { None };
+
+        Ok(CertificatePage {
+            cert_ids,
+            next_cursor,
+            total,
+        })
+    }
+}
+
+// Internal helpers shared by the contract entrypoints
+impl Contract {
+    /// Read the certificate IDs at issuance positions `start..end`
+    fn certificate_ids(env: &Env, start: u32, end: u32) -> Vec<String> {
+        let mut cert_ids = Vec::new(env);
+        for index in start..end {
+            let key = DataKey::CertificateIndex(index);
+            let cert_id: String = env.storage().persistent().get(&key).unwrap();
+            Self::extend_persistent_ttl(env, &key);
+            cert_ids.push_back(cert_id);
         }
 
         cert_ids
     }
+
+    /// Read the TTL configuration, falling back to the defaults
+    fn ttl_config(env: &Env) -> TtlConfig {
+        env.storage().instance().get(&CONFIG).unwrap_or(TtlConfig {
+            threshold: DEFAULT_TTL_THRESHOLD,
+            extend_to: DEFAULT_TTL_EXTEND_TO,
+        })
+    }
+
+    /// Extend the TTL of the contract instance once it drops below the threshold
+    fn extend_instance_ttl(env: &Env) {
+        let config = Self::ttl_config(env);
+        env.storage()
+            .instance()
+            .extend_ttl(config.threshold, config.extend_to);
+    }
+
+    /// Extend the TTL of a persistent entry once it drops below the threshold
+    fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+        let config = Self::ttl_config(env);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, config.threshold, config.extend_to);
+    }
 }
 
 mod test;
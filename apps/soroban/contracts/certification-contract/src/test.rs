@@ -1,12 +1,15 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{storage::Persistent, Address as _, Events, Ledger},
+    Address, Env, IntoVal, String,
+};
 
 /// Helper function to create a test environment and contract client
 fn setup() -> (Env, Address, ContractClient<'static>) {
     let env = Env::default();
-    let contract_id = env.register_contract(None, Contract);
+    let contract_id = env.register(Contract, ());
     let client = ContractClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
 
@@ -49,7 +52,7 @@ fn test_issue_certificate() {
     let cert_details = client.get_certificate_details(&cert_id);
     assert_eq!(cert_details.owner, owner);
     assert_eq!(cert_details.metadata_hash, metadata_hash);
-    assert_eq!(cert_details.is_valid, true);
+    assert!(cert_details.is_valid);
 }
 
 #[test]
@@ -96,7 +99,7 @@ fn test_revoke_certificate() {
 
     // Verify the certificate is valid
     let cert_details = client.get_certificate_details(&cert_id);
-    assert_eq!(cert_details.is_valid, true);
+    assert!(cert_details.is_valid);
 
     // Revoke the certificate as admin
     env.mock_all_auths();
@@ -104,7 +107,7 @@ fn test_revoke_certificate() {
 
     // Verify the certificate is now invalid
     let cert_details = client.get_certificate_details(&cert_id);
-    assert_eq!(cert_details.is_valid, false);
+    assert!(!cert_details.is_valid);
 }
 
 #[test]
@@ -154,12 +157,12 @@ fn test_verify_certificate() {
 
     // Verify with correct metadata hash
     let is_valid = client.verify_certificate(&cert_id, &metadata_hash);
-    assert_eq!(is_valid, true);
+    assert!(is_valid);
 
     // Verify with incorrect metadata hash
     let wrong_hash = String::from_str(&env, "wrong-hash");
     let is_valid = client.verify_certificate(&cert_id, &wrong_hash);
-    assert_eq!(is_valid, false);
+    assert!(!is_valid);
 
     // Revoke the certificate
     env.mock_all_auths();
@@ -167,7 +170,7 @@ fn test_verify_certificate() {
 
     // Verify the revoked certificate
     let is_valid = client.verify_certificate(&cert_id, &metadata_hash);
-    assert_eq!(is_valid, false);
+    assert!(!is_valid);
 }
 
 #[test]
@@ -203,6 +206,58 @@ fn test_list_certificates() {
     // We don't need to test this here as it's covered in the unauthorized tests
 }
 
+#[test]
+/// Test paginated certificate listing
+fn test_list_certificates_page() {
+    let (env, admin, client) = setup();
+
+    // Initialize the contract
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    // Issue five certificates
+    let owner = Address::generate(&env);
+    let metadata_hash = String::from_str(&env, "hash");
+    let ids = ["cert-1", "cert-2", "cert-3", "cert-4", "cert-5"];
+    for id in ids.iter() {
+        client.issue_certificate(&String::from_str(&env, id), &owner, &metadata_hash);
+    }
+
+    // Walk the registry two IDs at a time
+    let first = client.list_certificates_page(&0, &2);
+    assert_eq!(first.total, 5);
+    assert_eq!(first.cert_ids.len(), 2);
+    assert_eq!(
+        first.cert_ids.get(0).unwrap(),
+        String::from_str(&env, "cert-1")
+    );
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client.list_certificates_page(&2, &2);
+    assert_eq!(
+        second.cert_ids.get(0).unwrap(),
+        String::from_str(&env, "cert-3")
+    );
+    assert_eq!(second.next_cursor, Some(4));
+
+    let last = client.list_certificates_page(&4, &2);
+    assert_eq!(last.cert_ids.len(), 1);
+    assert_eq!(
+        last.cert_ids.get(0).unwrap(),
+        String::from_str(&env, "cert-5")
+    );
+    assert_eq!(last.next_cursor, None);
+
+    // A cursor past the end returns an empty page
+    let empty = client.list_certificates_page(&10, &2);
+    assert_eq!(empty.cert_ids.len(), 0);
+    assert_eq!(empty.total, 5);
+
+    // A zero page size is rejected
+    let result = client.try_list_certificates_page(&0, &0);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+}
+
 #[test]
 /// Test certificate not found error
 fn test_certificate_not_found() {
@@ -225,3 +280,151 @@ fn test_certificate_not_found() {
     // Verify it fails with certificate not found error
     assert!(result.is_err());
 }
+
+#[test]
+/// Test each certificate is stored in its own persistent entry
+fn test_certificate_storage_layout() {
+    let (env, admin, client) = setup();
+
+    // Initialize the contract
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    // Issue a certificate
+    let cert_id = String::from_str(&env, "cert-123");
+    let owner = Address::generate(&env);
+    let metadata_hash = String::from_str(&env, "abcdef123456");
+    client.issue_certificate(&cert_id, &owner, &metadata_hash);
+
+    // Verify the certificate and its index entry are in persistent storage
+    env.as_contract(&client.address, || {
+        let persistent = env.storage().persistent();
+        assert!(persistent.has(&DataKey::Certificate(cert_id.clone())));
+        assert_eq!(
+            persistent.get(&DataKey::CertificateIndex(0)),
+            Some(cert_id.clone())
+        );
+    });
+}
+
+#[test]
+/// Test certificates are kept in live state on write, on read and on demand
+fn test_certificate_ttl() {
+    let (env, admin, client) = setup();
+
+    // Initialize the contract
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    // Issue a certificate
+    let cert_id = String::from_str(&env, "cert-123");
+    let owner = Address::generate(&env);
+    client.issue_certificate(&cert_id, &owner, &String::from_str(&env, "hash"));
+
+    let config = client.get_ttl_config();
+    let cert_ttl = || {
+        env.as_contract(&client.address, || {
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Certificate(cert_id.clone()))
+        })
+    };
+
+    // Issuing extends the new entry to the configured TTL
+    assert_eq!(cert_ttl(), config.extend_to);
+
+    // Reading an entry close to expiry extends it again
+    let elapsed = config.extend_to - config.threshold + 1;
+    env.ledger().with_mut(|li| li.sequence_number += elapsed);
+    client.get_certificate_details(&cert_id);
+    assert_eq!(cert_ttl(), config.extend_to);
+
+    // Anyone can extend a certificate to the full TTL
+    env.ledger().with_mut(|li| li.sequence_number += 100);
+    env.set_auths(&[]);
+    client.extend_certificate_ttl(&cert_id);
+    assert_eq!(cert_ttl(), config.extend_to);
+
+    let result = client.try_extend_certificate_ttl(&String::from_str(&env, "missing"));
+    assert_eq!(result, Err(Ok(Error::CertificateNotFound)));
+}
+
+#[test]
+/// Test updating the TTL configuration
+fn test_set_ttl_config() {
+    let (env, admin, client) = setup();
+
+    // Initialize the contract
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    // Update the configuration as admin
+    client.set_ttl_config(&10_000, &50_000);
+    let config = client.get_ttl_config();
+    assert_eq!(config.threshold, 10_000);
+    assert_eq!(config.extend_to, 50_000);
+
+    // Invalid thresholds are rejected
+    let result = client.try_set_ttl_config(&0, &50_000);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    let result = client.try_set_ttl_config(&60_000, &50_000);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    let result = client.try_set_ttl_config(&10_000, &u32::MAX);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+
+    // Without admin authorization the update fails
+    env.set_auths(&[]);
+    let result = client.try_set_ttl_config(&10_000, &50_000);
+    assert!(result.is_err());
+}
+
+#[test]
+/// Test the owner can renounce a certificate, leaving a tombstone behind
+fn test_renounce_certificate() {
+    let (env, admin, client) = setup();
+
+    // Initialize the contract
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    // Issue a certificate
+    let cert_id = String::from_str(&env, "cert-123");
+    let owner = Address::generate(&env);
+    let metadata_hash = String::from_str(&env, "abcdef123456");
+    client.issue_certificate(&cert_id, &owner, &metadata_hash);
+
+    // Renounce it and check the owner authorized the call
+    client.renounce_certificate(&cert_id);
+    assert_eq!(env.auths()[0].0, owner);
+
+    // Verify the event was emitted
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: Vec<soroban_sdk::Val> =
+        (symbol_short!("RENOUNCED"), cert_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: Address = data.into_val(&env);
+    assert_eq!(data, owner);
+
+    // The metadata is gone and the certificate no longer verifies
+    let cert_details = client.get_certificate_details(&cert_id);
+    assert!(cert_details.is_renounced);
+    assert!(!cert_details.is_valid);
+    assert_eq!(cert_details.metadata_hash, String::from_str(&env, ""));
+    assert!(!client.verify_certificate(&cert_id, &metadata_hash));
+
+    // The ID cannot be reused and the certificate cannot be renounced twice
+    let result = client.try_issue_certificate(&cert_id, &owner, &metadata_hash);
+    assert_eq!(result, Err(Ok(Error::CertificateAlreadyExists)));
+    let result = client.try_renounce_certificate(&cert_id);
+    assert_eq!(result, Err(Ok(Error::CertificateRenounced)));
+
+    // Revoked certificates cannot be renounced, so the revocation stays on record
+    let revoked_id = String::from_str(&env, "cert-456");
+    client.issue_certificate(&revoked_id, &owner, &metadata_hash);
+    client.revoke_certificate(&revoked_id);
+    let result = client.try_renounce_certificate(&revoked_id);
+    assert_eq!(result, Err(Ok(Error::CertificateRevoked)));
+    let cert_details = client.get_certificate_details(&revoked_id);
+    assert!(!cert_details.is_renounced);
+    assert_eq!(cert_details.metadata_hash, metadata_hash);
+}
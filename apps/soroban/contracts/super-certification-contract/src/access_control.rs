@@ -1,7 +1,7 @@
 use crate::errors::Error;
 use crate::events::Events;
-use crate::storage::{Storage, StorageKeys};
-use soroban_sdk::{symbol_short, Address, Env, Map, Symbol, Vec};
+use crate::storage::Storage;
+use soroban_sdk::{symbol_short, Address, Env, Symbol, Vec};
 
 /// Role definitions
 pub struct Roles;
@@ -36,11 +36,22 @@ impl AccessControl {
     }
 
     /// Grant a role to an address
-    pub fn grant_role(env: &Env, address: &Address, role: &Symbol) -> Result<(), Error> {
+    pub fn grant_role(
+        env: &Env,
+        caller: &Address,
+        address: &Address,
+        role: &Symbol,
+    ) -> Result<(), Error> {
         // Only admin can grant roles
-        let admin = Storage::get_admin(env);
-        admin.require_auth();
+        Self::require_admin(env, caller)?;
 
+        Self::assign_role(env, address, role, caller);
+
+        Ok(())
+    }
+
+    /// Assign a role to an address without checking authorization
+    pub fn assign_role(env: &Env, address: &Address, role: &Symbol, granted_by: &Address) {
         // Get the roles map
         let mut roles = Storage::get_roles(env);
 
@@ -51,7 +62,7 @@ impl AccessControl {
 
             // Check if the address already has the role
             if address_roles.contains(role) {
-                return Ok(());
+                return;
             }
         } else {
             address_roles = Vec::new(env);
@@ -65,23 +76,46 @@ impl AccessControl {
         Storage::set_roles(env, &roles);
 
         // Emit event
-        Events::role_granted(env, address, role, &admin);
-
-        Ok(())
+        Events::role_granted(env, address, role, granted_by);
     }
 
     /// Revoke a role from an address
-    pub fn revoke_role(env: &Env, address: &Address, role: &Symbol) -> Result<(), Error> {
+    pub fn revoke_role(
+        env: &Env,
+        caller: &Address,
+        address: &Address,
+        role: &Symbol,
+    ) -> Result<(), Error> {
         // Only admin can revoke roles
-        let admin = Storage::get_admin(env);
-        admin.require_auth();
+        Self::require_admin(env, caller)?;
+
+        Self::remove_role(env, address, role, caller);
+
+        Ok(())
+    }
+
+    /// Renounce a role held by the caller
+    pub fn renounce_role(env: &Env, caller: &Address, role: &Symbol) -> Result<(), Error> {
+        // Require authorization from the caller
+        caller.require_auth();
+
+        if !Self::has_role(env, caller, role) {
+            return Err(Error::RoleNotFound);
+        }
+
+        Self::remove_role(env, caller, role, caller);
 
+        Ok(())
+    }
+
+    /// Remove a role from an address without checking authorization
+    fn remove_role(env: &Env, address: &Address, role: &Symbol, revoked_by: &Address) {
         // Get the roles map
         let mut roles = Storage::get_roles(env);
 
         // Check if the address has roles
         if !roles.contains_key(address.clone()) {
-            return Ok(());
+            return;
         }
 
         // Get the address roles
@@ -111,64 +145,71 @@ impl AccessControl {
             Storage::set_roles(env, &roles);
 
             // Emit event
-            Events::role_revoked(env, address, role, &admin);
+            Events::role_revoked(env, address, role, revoked_by);
         }
-
-        Ok(())
     }
 
     /// Check if the caller has a specific role
-    pub fn require_role(env: &Env, role: &Symbol) -> Result<(), Error> {
-        // Get the caller
-        let auth = env.current_contract_address();
+    pub fn require_role(env: &Env, caller: &Address, role: &Symbol) -> Result<(), Error> {
+        // Require authorization from the caller
+        caller.require_auth();
 
         // Check if the caller has the role
-        if !Self::has_role(env, &auth, role) {
+        if !Self::has_role(env, caller, role) {
             return Err(Error::Unauthorized);
         }
 
-        // Require authorization from the caller
-        auth.require_auth();
-
         Ok(())
     }
 
     /// Check if the caller is an admin
-    pub fn require_admin(env: &Env) -> Result<(), Error> {
-        Self::require_role(env, &Roles::ADMIN)
+    pub fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
+        Self::require_role(env, caller, &Roles::ADMIN)
     }
 
     /// Check if the caller is an issuer
-    pub fn require_issuer(env: &Env) -> Result<(), Error> {
+    pub fn require_issuer(env: &Env, caller: &Address) -> Result<(), Error> {
         // Admin can also issue
-        if Self::has_role(env, &env.current_contract_address(), &Roles::ADMIN) {
-            env.current_contract_address().require_auth();
+        if Self::has_role(env, caller, &Roles::ADMIN) {
+            caller.require_auth();
+            return Ok(());
+        }
+
+        Self::require_role(env, caller, &Roles::ISSUER)
+    }
+
+    /// Check if the caller can read certificate lists
+    pub fn require_verifier(env: &Env, caller: &Address) -> Result<(), Error> {
+        // Admins and issuers can also list certificates
+        if Self::has_role(env, caller, &Roles::ADMIN) || Self::has_role(env, caller, &Roles::ISSUER)
+        {
+            caller.require_auth();
             return Ok(());
         }
 
-        Self::require_role(env, &Roles::ISSUER)
+        Self::require_role(env, caller, &Roles::VERIFIER)
     }
 
     /// Check if the caller is a revoker
-    pub fn require_revoker(env: &Env) -> Result<(), Error> {
+    pub fn require_revoker(env: &Env, caller: &Address) -> Result<(), Error> {
         // Admin can also revoke
-        if Self::has_role(env, &env.current_contract_address(), &Roles::ADMIN) {
-            env.current_contract_address().require_auth();
+        if Self::has_role(env, caller, &Roles::ADMIN) {
+            caller.require_auth();
             return Ok(());
         }
 
-        Self::require_role(env, &Roles::REVOKER)
+        Self::require_role(env, caller, &Roles::REVOKER)
     }
 
     /// Check if the caller is an authority manager
-    pub fn require_auth_manager(env: &Env) -> Result<(), Error> {
+    pub fn require_auth_manager(env: &Env, caller: &Address) -> Result<(), Error> {
         // Admin can also manage authorities
-        if Self::has_role(env, &env.current_contract_address(), &Roles::ADMIN) {
-            env.current_contract_address().require_auth();
+        if Self::has_role(env, caller, &Roles::ADMIN) {
+            caller.require_auth();
             return Ok(());
         }
 
-        Self::require_role(env, &Roles::AUTH_MANAGER)
+        Self::require_role(env, caller, &Roles::AUTH_MANAGER)
     }
 
     /// Get all roles for an address
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracterror, Symbol};
+use soroban_sdk::contracterror;
 
 /// Error types for the SuperCertification contract
 #[contracterror]
@@ -45,4 +45,42 @@ pub enum Error {
     OperationNotSupported = 19,
     /// Error when a parameter is invalid
     InvalidParameter = 20,
+    /// Error when there are no pending data migrations
+    MigrationNotRequired = 21,
+    /// Error when a soulbound certificate is transferred
+    CertificateNotTransferable = 22,
+    /// Error when a certificate has been renounced by its owner
+    CertificateRenounced = 23,
+    /// Error when a certificate has no pending claim
+    ClaimNotFound = 24,
+    /// Error when a pending claim has expired
+    ClaimExpired = 25,
+    /// Error when a certificate type needs approval from several issuers
+    ApprovalRequired = 26,
+    /// Error when a certificate draft does not exist
+    DraftNotFound = 27,
+    /// Error when a certificate draft has expired
+    DraftExpired = 28,
+    /// Error when an issuer approves the same draft twice
+    AlreadyApproved = 29,
+    /// Error when an issuer has used up its issuance quota
+    QuotaExceeded = 30,
+    /// Error when the issuance fee cannot be transferred
+    FeePaymentFailed = 31,
+    /// Error when a certificate template already exists
+    TemplateAlreadyExists = 32,
+    /// Error when a certificate template does not exist
+    TemplateNotFound = 33,
+    /// Error when a certificate template is inactive
+    TemplateInactive = 34,
+    /// Error when a credential schema already exists
+    SchemaAlreadyExists = 35,
+    /// Error when a credential schema does not exist
+    SchemaNotFound = 36,
+    /// Error when a certificate batch already exists
+    BatchAlreadyExists = 37,
+    /// Error when a certificate batch does not exist
+    BatchNotFound = 38,
+    /// Error when the metadata of a certificate with private fields is amended
+    PrivateCertificate = 39,
 }
@@ -1,5 +1,5 @@
-use crate::types::{CertificateStatus, CertificateType};
-use soroban_sdk::{symbol_short, Address, Env, String, Symbol};
+use crate::types::{CertificateStatus, CertificateType, RevocationReason};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol};
 
 /// Event topics for the contract
 pub struct EventTopics;
@@ -9,12 +9,38 @@ impl EventTopics {
     pub const CERTIFICATE_ISSUED: Symbol = symbol_short!("CERT_ISS");
     /// Certificate revoked event topic
     pub const CERTIFICATE_REVOKED: Symbol = symbol_short!("CERT_REV");
+    /// Certificate draft proposed event topic
+    pub const CERTIFICATE_PROPOSED: Symbol = symbol_short!("CERT_PRP");
+    /// Certificate draft approved event topic
+    pub const CERTIFICATE_APPROVED: Symbol = symbol_short!("CERT_APR");
+    /// Certificate updated event topic
+    pub const CERTIFICATE_UPDATED: Symbol = symbol_short!("CERT_UPD");
+    /// Certificate claimed with a claim key event topic
+    pub const CERTIFICATE_CLAIMED: Symbol = symbol_short!("CERT_CLM");
+    /// Certificate renounced by its owner event topic
+    pub const CERTIFICATE_RENOUNCED: Symbol = symbol_short!("CERT_REN");
+    /// Certificate transferred by its owner event topic
+    pub const CERTIFICATE_TRANSFERRED: Symbol = symbol_short!("CERT_TRF");
+    /// Certificate owner reassigned by an issuer event topic
+    pub const OWNER_REASSIGNED: Symbol = symbol_short!("OWNR_RSG");
     /// Certificate status changed event topic
     pub const STATUS_CHANGED: Symbol = symbol_short!("STATUS_C");
     /// Authority added event topic
     pub const AUTHORITY_ADDED: Symbol = symbol_short!("AUTH_ADD");
     /// Authority updated event topic
     pub const AUTHORITY_UPDATED: Symbol = symbol_short!("AUTH_UPD");
+    /// Template created event topic
+    pub const TEMPLATE_CREATED: Symbol = symbol_short!("TMPL_ADD");
+    /// Template updated event topic
+    pub const TEMPLATE_UPDATED: Symbol = symbol_short!("TMPL_UPD");
+    /// Credential schema registered event topic
+    pub const SCHEMA_REGISTERED: Symbol = symbol_short!("SCH_ADD");
+    /// Custom fields rejected by a credential schema event topic
+    pub const SCHEMA_VIOLATION: Symbol = symbol_short!("SCH_FAIL");
+    /// Certificate batch anchored event topic
+    pub const BATCH_ANCHORED: Symbol = symbol_short!("BTCH_ANC");
+    /// Certificate batch member revoked event topic
+    pub const BATCH_MEMBER_REVOKED: Symbol = symbol_short!("BTCH_REV");
     /// Role granted event topic
     pub const ROLE_GRANTED: Symbol = symbol_short!("ROLE_GRT");
     /// Role revoked event topic
@@ -41,6 +67,54 @@ impl Events {
         );
     }
 
+    /// Emit certificate proposed event
+    pub fn certificate_proposed(
+        env: &Env,
+        cert_id: &String,
+        proposer: &Address,
+        cert_type: &CertificateType,
+        expires_at: u64,
+    ) {
+        env.events().publish(
+            (EventTopics::CERTIFICATE_PROPOSED, cert_id.clone()),
+            (proposer.clone(), cert_type.clone(), expires_at),
+        );
+    }
+
+    /// Emit certificate approved event
+    pub fn certificate_approved(
+        env: &Env,
+        cert_id: &String,
+        approver: &Address,
+        approvals: u32,
+        threshold: u32,
+    ) {
+        env.events().publish(
+            (EventTopics::CERTIFICATE_APPROVED, cert_id.clone()),
+            (approver.clone(), approvals, threshold),
+        );
+    }
+
+    /// Emit certificate updated event
+    pub fn certificate_updated(
+        env: &Env,
+        cert_id: &String,
+        old_hash: &String,
+        new_hash: &String,
+        version: u32,
+        updated_by: &Address,
+    ) {
+        env.events().publish(
+            (EventTopics::CERTIFICATE_UPDATED, cert_id.clone()),
+            (
+                old_hash.clone(),
+                new_hash.clone(),
+                version,
+                updated_by.clone(),
+            ),
+        );
+    }
+
     /// Emit certificate status changed event
     pub fn status_changed(
         env: &Env,
@@ -60,11 +134,50 @@ impl Events {
         env: &Env,
         cert_id: &String,
         revoked_by: &Address,
-        reason: &Option<String>,
+        reason: &RevocationReason,
+        details: &Option<String>,
     ) {
         env.events().publish(
             (EventTopics::CERTIFICATE_REVOKED, cert_id.clone()),
-            (revoked_by.clone(), reason.clone()),
+            (revoked_by.clone(), reason.clone(), details.clone()),
+        );
+    }
+
+    /// Emit certificate claimed event
+    pub fn certificate_claimed(env: &Env, cert_id: &String, owner: &Address) {
+        env.events().publish(
+            (EventTopics::CERTIFICATE_CLAIMED, cert_id.clone()),
+            owner.clone(),
+        );
+    }
+
+    /// Emit certificate renounced event
+    pub fn certificate_renounced(env: &Env, cert_id: &String, owner: &Address) {
+        env.events().publish(
+            (EventTopics::CERTIFICATE_RENOUNCED, cert_id.clone()),
+            owner.clone(),
+        );
+    }
+
+    /// Emit certificate transferred event
+    pub fn certificate_transferred(env: &Env, cert_id: &String, from: &Address, to: &Address) {
+        env.events().publish(
+            (EventTopics::CERTIFICATE_TRANSFERRED, cert_id.clone()),
+            (from.clone(), to.clone()),
+        );
+    }
+
+    /// Emit owner reassigned event
+    pub fn owner_reassigned(
+        env: &Env,
+        cert_id: &String,
+        old_owner: &Address,
+        new_owner: &Address,
+        reassigned_by: &Address,
+    ) {
+        env.events().publish(
+            (EventTopics::OWNER_REASSIGNED, cert_id.clone()),
+            (old_owner.clone(), new_owner.clone(), reassigned_by.clone()),
         );
     }
 
@@ -80,6 +193,78 @@ impl Events {
             .publish((EventTopics::AUTHORITY_UPDATED,), (authority, updated_by));
     }
 
+    /// Emit template created event
+    pub fn template_created(env: &Env, template_id: &String, created_by: &Address) {
+        env.events().publish(
+            (EventTopics::TEMPLATE_CREATED, template_id.clone()),
+            created_by.clone(),
+        );
+    }
+
+    /// Emit template updated event
+    pub fn template_updated(
+        env: &Env,
+        template_id: &String,
+        version: u32,
+        is_active: bool,
+        updated_by: &Address,
+    ) {
+        env.events().publish(
+            (EventTopics::TEMPLATE_UPDATED, template_id.clone()),
+            (version, is_active, updated_by.clone()),
+        );
+    }
+
+    /// Emit credential schema registered event
+    pub fn schema_registered(env: &Env, schema_id: &String, registered_by: &Address) {
+        env.events().publish(
+            (EventTopics::SCHEMA_REGISTERED, schema_id.clone()),
+            registered_by.clone(),
+        );
+    }
+
+    /// Emit schema violation event
+    pub fn schema_violation(env: &Env, cert_id: &String, schema_id: &String, field: &String) {
+        env.events().publish(
+            (EventTopics::SCHEMA_VIOLATION, cert_id.clone()),
+            (schema_id.clone(), field.clone()),
+        );
+    }
+
+    /// Emit certificate batch anchored event
+    pub fn batch_anchored(
+        env: &Env,
+        batch_id: &String,
+        issuer: &Address,
+        merkle_root: &BytesN<32>,
+        count: u32,
+        cert_type: &CertificateType,
+    ) {
+        env.events().publish(
+            (EventTopics::BATCH_ANCHORED, batch_id.clone()),
+            (
+                issuer.clone(),
+                merkle_root.clone(),
+                count,
+                cert_type.clone(),
+            ),
+        );
+    }
+
+    /// Emit certificate batch member revoked event
+    pub fn batch_member_revoked(
+        env: &Env,
+        batch_id: &String,
+        leaf_hash: &BytesN<32>,
+        reason: &RevocationReason,
+        revoked_by: &Address,
+    ) {
+        env.events().publish(
+            (EventTopics::BATCH_MEMBER_REVOKED, batch_id.clone()),
+            (leaf_hash.clone(), reason.clone(), revoked_by.clone()),
+        );
+    }
+
     /// Emit role granted event
     pub fn role_granted(env: &Env, address: &Address, role: &Symbol, granted_by: &Address) {
         env.events()
@@ -96,12 +281,12 @@ impl Events {
     pub fn contract_upgraded(
         env: &Env,
         old_version: &u32,
-        new_version: &u32,
+        new_wasm_hash: &BytesN<32>,
         upgraded_by: &Address,
     ) {
         env.events().publish(
             (EventTopics::CONTRACT_UPGRADED,),
-            (old_version, new_version, upgraded_by),
+            (old_version, new_wasm_hash, upgraded_by),
         );
     }
 }
@@ -130,12 +315,132 @@ pub fn emit_status_changed(
     Events::status_changed(env, cert_id, old_status, new_status, changed_by);
 }
 
+/// Emit certificate proposed event
+pub fn emit_certificate_proposed(
+    env: &Env,
+    cert_id: &String,
+    proposer: &Address,
+    cert_type: &CertificateType,
+    expires_at: u64,
+) {
+    Events::certificate_proposed(env, cert_id, proposer, cert_type, expires_at);
+}
+
+/// Emit certificate approved event
+pub fn emit_certificate_approved(
+    env: &Env,
+    cert_id: &String,
+    approver: &Address,
+    approvals: u32,
+    threshold: u32,
+) {
+    Events::certificate_approved(env, cert_id, approver, approvals, threshold);
+}
+
+/// Emit certificate updated event
+pub fn emit_certificate_updated(
+    env: &Env,
+    cert_id: &String,
+    old_hash: &String,
+    new_hash: &String,
+    version: u32,
+    updated_by: &Address,
+) {
+    Events::certificate_updated(env, cert_id, old_hash, new_hash, version, updated_by);
+}
+
 /// Emit certificate revoked event
 pub fn emit_certificate_revoked(
     env: &Env,
     cert_id: &String,
     revoked_by: &Address,
-    reason: &Option<String>,
+    reason: &RevocationReason,
+    details: &Option<String>,
 ) {
-    Events::emit_certificate_revoked(env, cert_id, revoked_by, reason);
+    Events::emit_certificate_revoked(env, cert_id, revoked_by, reason, details);
+}
+
+/// Emit certificate claimed event
+pub fn emit_certificate_claimed(env: &Env, cert_id: &String, owner: &Address) {
+    Events::certificate_claimed(env, cert_id, owner);
+}
+
+/// Emit certificate renounced event
+pub fn emit_certificate_renounced(env: &Env, cert_id: &String, owner: &Address) {
+    Events::certificate_renounced(env, cert_id, owner);
+}
+
+/// Emit certificate transferred event
+pub fn emit_certificate_transferred(env: &Env, cert_id: &String, from: &Address, to: &Address) {
+    Events::certificate_transferred(env, cert_id, from, to);
+}
+
+/// Emit owner reassigned event
+pub fn emit_owner_reassigned(
+    env: &Env,
+    cert_id: &String,
+    old_owner: &Address,
+    new_owner: &Address,
+    reassigned_by: &Address,
+) {
+    Events::owner_reassigned(env, cert_id, old_owner, new_owner, reassigned_by);
+}
+
+/// Emit template created event
+pub fn emit_template_created(env: &Env, template_id: &String, created_by: &Address) {
+    Events::template_created(env, template_id, created_by);
+}
+
+/// Emit template updated event
+pub fn emit_template_updated(
+    env: &Env,
+    template_id: &String,
+    version: u32,
+    is_active: bool,
+    updated_by: &Address,
+) {
+    Events::template_updated(env, template_id, version, is_active, updated_by);
+}
+
+/// Emit credential schema registered event
+pub fn emit_schema_registered(env: &Env, schema_id: &String, registered_by: &Address) {
+    Events::schema_registered(env, schema_id, registered_by);
+}
+
+/// Emit schema violation event
+pub fn emit_schema_violation(env: &Env, cert_id: &String, schema_id: &String, field: &String) {
+    Events::schema_violation(env, cert_id, schema_id, field);
+}
+
+/// Emit certificate batch anchored event
+pub fn emit_batch_anchored(
+    env: &Env,
+    batch_id: &String,
+    issuer: &Address,
+    merkle_root: &BytesN<32>,
+    count: u32,
+    cert_type: &CertificateType,
+) {
+    Events::batch_anchored(env, batch_id, issuer, merkle_root, count, cert_type);
+}
+
+/// Emit certificate batch member revoked event
+pub fn emit_batch_member_revoked(
+    env: &Env,
+    batch_id: &String,
+    leaf_hash: &BytesN<32>,
+    reason: &RevocationReason,
+    revoked_by: &Address,
+) {
+    Events::batch_member_revoked(env, batch_id, leaf_hash, reason, revoked_by);
+}
+
+/// Emit authority added event
+pub fn emit_authority_added(env: &Env, authority: &Address, added_by: &Address) {
+    Events::authority_added(env, authority, added_by);
+}
+
+/// Emit authority updated event
+pub fn emit_authority_updated(env: &Env, authority: &Address, updated_by: &Address) {
+    Events::authority_updated(env, authority, updated_by);
 }
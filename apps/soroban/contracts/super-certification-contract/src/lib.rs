@@ -1,10 +1,12 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Map, String, Symbol, Vec};
+#![allow(clippy::too_many_arguments)]
+use soroban_sdk::{contract, contractimpl, token, Address, BytesN, Env, Map, String, Symbol, Vec};
 
 // Import modules
 mod access_control;
 mod errors;
 mod events;
+mod migrations;
 mod storage;
 mod types;
 mod utils;
@@ -12,10 +14,20 @@ mod utils;
 // Re-export types for external use
 pub use errors::Error;
 pub use types::{
-    CertificateDetails, CertificateMetadata, CertificateStatus, CertificateType,
-    CertificationAuthority, VerificationResult,
+    BatchVerificationResult, CertificateBatch, CertificateDetails, CertificateDraft,
+    CertificateHistoryPage, CertificateMetadata, CertificatePage, CertificateStatus,
+    CertificateTemplate, CertificateType, CertificateVersion, CertificationAuthority,
+    ContractConfig, CredentialSchema, FieldType, IssuerQuota, IssuerUsage, PendingClaim,
+    RevocationReason, RevocationRecord, SchemaField, SuspensionRecord, VerificationResult,
 };
 
+/// Version of this contract code
+///
+/// Version 1 is the first release that can be upgraded. Bump it in every
+/// release that changes the stored data layout, and add steps to `migrations`
+/// that rewrite the data of the previous version.
+pub const CONTRACT_VERSION: u32 = 1;
+
 // Main contract struct
 #[contract]
 pub struct SuperCertificationContract;
@@ -37,18 +49,139 @@ impl SuperCertificationContract {
             return Err(Error::AlreadyInitialized);
         }
 
-        // Set the admin
-        storage::set_admin(&env, &authority);
+        // Require authorization from the authority
+        authority.require_auth();
 
-        // Grant admin role to the authority
-        access_control::AccessControl::grant_role(&env, &authority, &access_control::Roles::ADMIN)?;
+        // Set the admin and initialize storage
+        storage::set_admin(&env, &authority);
+        storage::init_all(&env);
 
-        // Grant issuer role to the authority
-        access_control::AccessControl::grant_role(
+        // Grant admin and issuer roles to the authority
+        access_control::AccessControl::assign_role(
+            &env,
+            &authority,
+            &access_control::Roles::ADMIN,
+            &authority,
+        );
+        access_control::AccessControl::assign_role(
             &env,
             &authority,
             &access_control::Roles::ISSUER,
-        )?;
+            &authority,
+        );
+
+        Ok(())
+    }
+
+    /// Upgrade the contract code to a new WASM binary
+    ///
+    /// The new code reports its own `CONTRACT_VERSION`. Stored data is kept as
+    /// is; call `migrate` afterwards to bring it up to that version.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address performing the upgrade (must be ADMIN)
+    /// * `new_wasm_hash` - The hash of the already uploaded WASM binary
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        // Only the admin can upgrade the contract
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        // Swap the contract code; the new code takes effect after this invocation
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        events::Events::contract_upgraded(&env, &CONTRACT_VERSION, &new_wasm_hash, &caller);
+
+        Ok(())
+    }
+
+    /// Run one page of the data migrations pending since the last upgrade
+    ///
+    /// Brings the stored data from its recorded version up to
+    /// `CONTRACT_VERSION`. Certificates are migrated a page at a time over the
+    /// issuance index, and the data version is only advanced after the last page.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address running the migration (must be ADMIN)
+    /// * `cursor` - Position to start at: 0 for the first page, then the cursor
+    ///   returned by the previous page
+    /// * `limit` - Maximum number of certificates to migrate (capped at 100)
+    ///
+    /// # Returns
+    /// * `Result<Option<u32>, Error>` - The cursor of the next page, `None` once
+    ///   the migration is complete, `MigrationNotRequired` if the data is already
+    ///   up to date, or `InvalidParameter` if `limit` is 0 or `cursor` is not
+    ///   where the previous page ended
+    pub fn migrate(
+        env: Env,
+        caller: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<Option<u32>, Error> {
+        // Only the admin can migrate the contract data
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        migrations::run_pending(&env, cursor, limit)
+    }
+
+    /// Get the version of the running contract code
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    ///
+    /// # Returns
+    /// * `u32` - The contract version
+    pub fn get_version(_env: Env) -> u32 {
+        CONTRACT_VERSION
+    }
+
+    /// Get the contract configuration
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    ///
+    /// # Returns
+    /// * `ContractConfig` - The current configuration
+    pub fn get_config(env: Env) -> ContractConfig {
+        storage::Storage::get_config(&env)
+    }
+
+    /// Set the TTL thresholds used to keep contract data in live state
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the configuration (must be ADMIN)
+    /// * `ttl_threshold` - Remaining TTL, in ledgers, below which entries are extended
+    /// * `ttl_extend_to` - TTL, in ledgers, that entries are extended to
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_ttl_config(
+        env: Env,
+        caller: Address,
+        ttl_threshold: u32,
+        ttl_extend_to: u32,
+    ) -> Result<(), Error> {
+        // Only the admin can change the configuration
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        // The threshold must be reachable and the target within the network limit
+        if ttl_threshold == 0
+            || ttl_threshold > ttl_extend_to
+            || ttl_extend_to > env.storage().max_ttl()
+        {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut config = storage::Storage::get_config(&env);
+        config.ttl_threshold = ttl_threshold;
+        config.ttl_extend_to = ttl_extend_to;
+        storage::Storage::set_config(&env, &config);
+        storage::Storage::extend_instance_ttl(&env);
 
         Ok(())
     }
@@ -57,265 +190,2946 @@ impl SuperCertificationContract {
     ///
     /// # Arguments
     /// * `env` - The environment object
+    /// * `caller` - The issuer of the certificate (ISSUER or ADMIN)
     /// * `cert_id` - The unique identifier for the certificate
     /// * `owner` - The address of the certificate owner
     /// * `metadata` - The certificate metadata
     /// * `signature` - Digital signature of the certificate
     /// * `cert_type` - The type of the certificate
     /// * `expiration_date` - The expiration date of the certificate
+    /// * `custom_fields` - Additional key-value fields, such as grade or cohort
     ///
     /// # Returns
     /// * `Result<(), Error>` - Success or an error
     pub fn issue_certificate(
         env: Env,
+        caller: Address,
         cert_id: String,
         owner: Address,
         metadata: String,
         signature: BytesN<64>,
         cert_type: CertificateType,
         expiration_date: u64,
+        custom_fields: Map<String, String>,
     ) -> Result<(), Error> {
         // Check if caller is authorized to issue certificates
-        let caller = env.current_contract_address();
-        if !access_control::AccessControl::has_role(&env, &caller, &access_control::Roles::ISSUER) {
-            return Err(Error::Unauthorized);
-        }
-
-        // Check if certificate already exists
-        if storage::has_certificate(&env, &cert_id) {
-            return Err(Error::CertificateAlreadyExists);
-        }
+        access_control::AccessControl::require_issuer(&env, &caller)?;
 
-        // Create certificate metadata
-        let metadata_obj = CertificateMetadata {
-            title: cert_id.clone(),
-            description: metadata.clone(),
-            issue_date: env.ledger().timestamp(),
+        let metadata = Self::new_metadata(
+            &env,
+            &cert_id,
+            metadata,
+            cert_type,
             expiration_date,
-            cert_type: cert_type.clone(),
-            custom_fields: Map::new(&env),
-        };
+            custom_fields,
+        );
+        Self::issue(&env, &caller, &cert_id, &owner, metadata, &signature, None)
+    }
 
-        // Create certificate details
-        let cert_details = CertificateDetails {
-            owner: owner.clone(),
-            issuer: caller.clone(),
-            metadata_hash: utils::bytes_to_string(
-                &env,
-                &utils::hash_metadata(&env, &cert_id, &metadata),
-            ),
-            metadata: metadata_obj,
-            status: CertificateStatus::Active,
-            signature,
-            version: 1,
-            revocation_reason: None,
-            last_updated: env.ledger().timestamp(),
-        };
+    /// Issue a certificate to whoever holds the secret of a claim key
+    ///
+    /// Until it is claimed, the certificate is held by the contract itself
+    /// and listed under the contract's address.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The issuer of the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `claim_key` - Ed25519 public key whose secret is given to the recipient
+    /// * `claim_expires_at` - Timestamp after which the certificate can no longer be claimed
+    /// * `metadata` - The certificate metadata
+    /// * `signature` - Digital signature of the certificate
+    /// * `cert_type` - The type of the certificate
+    /// * `expiration_date` - The expiration date of the certificate
+    /// * `custom_fields` - Additional key-value fields, such as grade or cohort
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn issue_claimable_certificate(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        claim_key: BytesN<32>,
+        claim_expires_at: u64,
+        metadata: String,
+        signature: BytesN<64>,
+        cert_type: CertificateType,
+        expiration_date: u64,
+        custom_fields: Map<String, String>,
+    ) -> Result<(), Error> {
+        // Check if caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
 
-        // Store the certificate
-        storage::set_certificate(&env, &cert_id, &cert_details);
+        // The claim window must end in the future
+        if claim_expires_at <= env.ledger().timestamp() {
+            return Err(Error::InvalidParameter);
+        }
 
-        // Emit certificate issued event
-        events::emit_certificate_issued(&env, &cert_id, &owner, &caller, &cert_type);
+        let metadata = Self::new_metadata(
+            &env,
+            &cert_id,
+            metadata,
+            cert_type,
+            expiration_date,
+            custom_fields,
+        );
+        Self::issue(
+            &env,
+            &caller,
+            &cert_id,
+            &env.current_contract_address(),
+            metadata,
+            &signature,
+            None,
+        )?;
+
+        storage::set_pending_claim(
+            &env,
+            &cert_id,
+            &PendingClaim {
+                claim_key,
+                expires_at: claim_expires_at,
+            },
+        );
 
         Ok(())
     }
 
-    /// Batch issue multiple certificates
+    /// Claim a certificate with a signature from the claim key it was issued to
+    ///
+    /// The signature is over `Utils::claim_digest(cert_id, claimant)`, so it
+    /// only works for the claimant it names and cannot be lifted from a
+    /// pending transaction by someone else. A bad signature aborts the call.
     ///
     /// # Arguments
     /// * `env` - The environment object
-    /// * `cert_ids` - The unique identifiers for the certificates
-    /// * `owners` - The addresses of the certificate owners
-    /// * `metadatas` - The certificate metadatas
-    /// * `signatures` - Digital signatures of the certificates
-    /// * `cert_types` - The types of the certificates
-    /// * `expiration_dates` - The expiration dates of the certificates
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `claimant` - The address that becomes the owner
+    /// * `signature` - Ed25519 signature of the claim digest by the claim key
     ///
     /// # Returns
-    /// * `Result<Vec<String>, Error>` - Failed certificate IDs
-    pub fn batch_issue_certificates(
+    /// * `Result<(), Error>` - Success or an error
+    pub fn claim_certificate(
         env: Env,
-        cert_ids: Vec<String>,
-        owners: Vec<Address>,
-        metadatas: Vec<String>,
-        signatures: Vec<BytesN<64>>,
-        cert_types: Vec<CertificateType>,
-        expiration_dates: Vec<u64>,
-    ) -> Result<Vec<String>, Error> {
-        // Check if caller is authorized to issue certificates
-        let caller = env.current_contract_address();
-        if !access_control::AccessControl::has_role(&env, &caller, &access_control::Roles::ISSUER) {
-            return Err(Error::Unauthorized);
-        }
+        cert_id: String,
+        claimant: Address,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        // Require authorization from the new owner
+        claimant.require_auth();
 
-        // Check if all vectors have the same length
-        let count = cert_ids.len();
-        if owners.len() != count
-            || metadatas.len() != count
-            || signatures.len() != count
-            || cert_types.len() != count
-            || expiration_dates.len() != count
-        {
-            return Err(Error::InvalidMetadata);
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
         }
 
-        // Process each certificate
-        let mut failed_certs = Vec::new(&env);
+        // Check the claim is still open
+        let claim = storage::get_pending_claim(&env, &cert_id).ok_or(Error::ClaimNotFound)?;
+        if env.ledger().timestamp() > claim.expires_at {
+            return Err(Error::ClaimExpired);
+        }
 
-        for i in 0..count {
-            let cert_id = cert_ids.get(i).unwrap();
-            let owner = owners.get(i).unwrap();
-            let metadata = metadatas.get(i).unwrap();
-            let signature = signatures.get(i).unwrap();
-            let cert_type = cert_types.get(i).unwrap();
-            let expiration_date = expiration_dates.get(i).unwrap();
+        // Only active certificates can be claimed
+        let mut certificate = storage::get_certificate(&env, &cert_id);
+        match utils::Utils::effective_status(&env, &certificate) {
+            CertificateStatus::Active => {}
+            CertificateStatus::Revoked => return Err(Error::CertificateRevoked),
+            CertificateStatus::Suspended => return Err(Error::CertificateSuspended),
+            CertificateStatus::Expired => return Err(Error::CertificateExpired),
+            CertificateStatus::Renounced => return Err(Error::CertificateRenounced),
+        }
 
-            // Try to issue the certificate
-            let result = SuperCertificationContract::issue_certificate(
-                env.clone(),
-                cert_id.clone(),
-                owner.clone(),
-                metadata.clone(),
-                signature.clone(),
-                cert_type.clone(),
-                expiration_date,
-            );
+        // The claim key must have signed this claimant's address
+        utils::Utils::verify_signature(
+            &env,
+            &utils::claim_digest(&env, &cert_id, &claimant),
+            &signature,
+            &claim.claim_key,
+        );
 
-            // If failed, add to the failed list
-            if result.is_err() {
-                failed_certs.push_back(cert_id.clone());
-            }
-        }
+        // Hand the certificate over; this also closes the claim
+        Self::move_to_owner(&env, &cert_id, &mut certificate, &claimant);
+        events::emit_certificate_claimed(&env, &cert_id, &claimant);
 
-        Ok(failed_certs)
+        Ok(())
     }
 
-    /// List certificates owned by a specific address
+    /// Get the pending claim of a certificate issued to a claim key
     ///
     /// # Arguments
     /// * `env` - The environment object
-    /// * `owner` - The address of the certificate owner
+    /// * `cert_id` - The unique identifier for the certificate
     ///
     /// # Returns
-    /// * `Vec<String>` - A list of certificate IDs owned by the address
-    pub fn list_certificates_by_owner(env: Env, owner: Address) -> Vec<String> {
-        // Get all certificates
-        let cert_ids = storage::get_all_certificate_ids(&env);
-        let mut owner_certs = Vec::new(&env);
-
-        // Filter certificates by owner
-        for id in cert_ids.iter() {
-            let cert = storage::get_certificate(&env, &id);
-            if cert.owner == owner {
-                owner_certs.push_back(id);
-            }
+    /// * `Result<PendingClaim, Error>` - The pending claim, or `ClaimNotFound` if
+    ///   the certificate was not issued to a claim key or was already claimed
+    pub fn get_pending_claim(env: Env, cert_id: String) -> Result<PendingClaim, Error> {
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
         }
 
-        owner_certs
+        storage::get_pending_claim(&env, &cert_id).ok_or(Error::ClaimNotFound)
     }
 
-    /// Update the status of a certificate
+    /// Issue a certificate whose fields are only committed to, for selective disclosure
+    ///
+    /// The certificate stores a Merkle root over salted field leaves instead of
+    /// its fields. The description is the hex-encoded root, so the metadata
+    /// hash and the issuer's signature cover it. Types whose credential schema
+    /// has required fields cannot be issued this way, as the fields are hidden.
     ///
     /// # Arguments
     /// * `env` - The environment object
+    /// * `caller` - The issuer of the certificate (ISSUER or ADMIN)
     /// * `cert_id` - The unique identifier for the certificate
-    /// * `status` - The new status for the certificate
-    /// * `reason` - Optional reason for the status change
+    /// * `owner` - The address of the certificate owner
+    /// * `fields_root` - Merkle root over the leaves built with `Utils::disclosure_leaf`
+    /// * `signature` - Digital signature of the certificate
+    /// * `cert_type` - The type of the certificate
+    /// * `expiration_date` - The expiration date of the certificate
     ///
     /// # Returns
     /// * `Result<(), Error>` - Success or an error
-    pub fn update_certificate_status(
+    pub fn issue_private_certificate(
         env: Env,
+        caller: Address,
         cert_id: String,
-        status: CertificateStatus,
-        reason: Option<String>,
+        owner: Address,
+        fields_root: BytesN<32>,
+        signature: BytesN<64>,
+        cert_type: CertificateType,
+        expiration_date: u64,
     ) -> Result<(), Error> {
-        // Check if the caller is authorized to update certificates
-        let caller = env.current_contract_address();
-        if !access_control::AccessControl::has_role(&env, &caller, &access_control::Roles::ISSUER) {
-            return Err(Error::Unauthorized);
-        }
+        // Check if caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
 
-        // Check if certificate exists
-        if !storage::has_certificate(&env, &cert_id) {
-            return Err(Error::CertificateNotFound);
+        // The fields are only committed to, so a schema that requires some cannot be met
+        if let Some(schema_id) = storage::Storage::get_type_schemas(&env).get(cert_type.clone()) {
+            let schema = storage::get_schema(&env, &schema_id).ok_or(Error::SchemaNotFound)?;
+            if schema.fields.iter().any(|field| field.required) {
+                return Err(Error::OperationNotSupported);
+            }
         }
 
-        // Get the certificate
+        let metadata = Self::new_metadata(
+            &env,
+            &cert_id,
+            utils::bytes_to_string(&env, &fields_root),
+            cert_type,
+            expiration_date,
+            Map::new(&env),
+        );
+        Self::issue(&env, &caller, &cert_id, &owner, metadata, &signature, None)?;
+
         let mut certificate = storage::get_certificate(&env, &cert_id);
+        certificate.fields_root = Some(fields_root);
+        storage::set_certificate(&env, &cert_id, &certificate);
 
-        // Store the old status for the event
-        let old_status = certificate.status.clone();
+        Ok(())
+    }
 
-        // Update the certificate status
-        certificate.status = status.clone();
+    /// Create a certificate template
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address creating the template (must be ADMIN)
+    /// * `template_id` - The unique identifier for the template
+    /// * `cert_type` - The type of the certificates issued from the template
+    /// * `title` - The title of the certificates issued from the template
+    /// * `description_pattern` - The description, with `{field}` placeholders for custom fields
+    /// * `default_validity_secs` - How long issued certificates are valid (0 for no expiration)
+    /// * `required_custom_fields` - Custom fields every certificate must be issued with
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn create_template(
+        env: Env,
+        caller: Address,
+        template_id: String,
+        cert_type: CertificateType,
+        title: String,
+        description_pattern: String,
+        default_validity_secs: u64,
+        required_custom_fields: Vec<String>,
+    ) -> Result<(), Error> {
+        // Only the admin can manage templates
+        access_control::AccessControl::require_admin(&env, &caller)?;
 
-        // Update revocation reason if status is Revoked
-        if status == CertificateStatus::Revoked {
-            certificate.revocation_reason = reason.clone();
+        if template_id.is_empty() {
+            return Err(Error::InvalidParameter);
+        }
+        if storage::get_template(&env, &template_id).is_some() {
+            return Err(Error::TemplateAlreadyExists);
         }
 
-        certificate.last_updated = env.ledger().timestamp();
+        let template = Self::new_template(
+            &env,
+            &caller,
+            template_id,
+            1,
+            cert_type,
+            title,
+            description_pattern,
+            default_validity_secs,
+            required_custom_fields,
+        )?;
+        storage::set_template(&env, &template);
 
-        // Update the certificate in storage
-        storage::set_certificate(&env, &cert_id, &certificate);
+        events::emit_template_created(&env, &template.template_id, &caller);
+
+        Ok(())
+    }
+
+    /// Update a certificate template, publishing it as a new version
+    ///
+    /// Certificates already issued keep the version they were issued from.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the template (must be ADMIN)
+    /// * `template_id` - The unique identifier for the template
+    /// * `cert_type` - The type of the certificates issued from the template
+    /// * `title` - The title of the certificates issued from the template
+    /// * `description_pattern` - The description, with `{field}` placeholders for custom fields
+    /// * `default_validity_secs` - How long issued certificates are valid (0 for no expiration)
+    /// * `required_custom_fields` - Custom fields every certificate must be issued with
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn update_template(
+        env: Env,
+        caller: Address,
+        template_id: String,
+        cert_type: CertificateType,
+        title: String,
+        description_pattern: String,
+        default_validity_secs: u64,
+        required_custom_fields: Vec<String>,
+    ) -> Result<(), Error> {
+        // Only the admin can manage templates
+        access_control::AccessControl::require_admin(&env, &caller)?;
 
-        // Emit status changed event
-        events::emit_status_changed(&env, &cert_id, &old_status, &status, &caller);
+        let current = storage::get_template(&env, &template_id).ok_or(Error::TemplateNotFound)?;
+        let mut template = Self::new_template(
+            &env,
+            &caller,
+            template_id,
+            current.version + 1,
+            cert_type,
+            title,
+            description_pattern,
+            default_validity_secs,
+            required_custom_fields,
+        )?;
+        template.is_active = current.is_active;
+        storage::set_template(&env, &template);
+
+        events::emit_template_updated(
+            &env,
+            &template.template_id,
+            template.version,
+            template.is_active,
+            &caller,
+        );
 
         Ok(())
     }
 
-    /// Update certificate metadata
+    /// Deactivate a certificate template so that no more certificates are issued from it
     ///
     /// # Arguments
     /// * `env` - The environment object
-    /// * `cert_id` - The unique identifier for the certificate
-    /// * `metadata` - The new metadata for the certificate
-    /// * `signature` - Digital signature of the updated certificate
+    /// * `caller` - The address deactivating the template (must be ADMIN)
+    /// * `template_id` - The unique identifier for the template
     ///
     /// # Returns
     /// * `Result<(), Error>` - Success or an error
-    pub fn update_certificate_metadata(
+    pub fn deactivate_template(
         env: Env,
-        cert_id: String,
-        metadata: String,
-        signature: BytesN<64>,
+        caller: Address,
+        template_id: String,
     ) -> Result<(), Error> {
-        // Check if the caller is authorized to issue certificates
-        let caller = env.current_contract_address();
-        if !access_control::AccessControl::has_role(&env, &caller, &access_control::Roles::ISSUER) {
-            return Err(Error::Unauthorized);
-        }
+        // Only the admin can manage templates
+        access_control::AccessControl::require_admin(&env, &caller)?;
 
-        // Check if certificate exists
-        if !storage::has_certificate(&env, &cert_id) {
-            return Err(Error::CertificateNotFound);
+        let mut template =
+            storage::get_template(&env, &template_id).ok_or(Error::TemplateNotFound)?;
+        if !template.is_active {
+            return Err(Error::TemplateInactive);
         }
 
-        // Get the certificate
-        let mut certificate = storage::get_certificate(&env, &cert_id);
+        template.is_active = false;
+        template.updated_by = caller.clone();
+        template.updated_at = env.ledger().timestamp();
+        storage::set_template(&env, &template);
 
-        // Create new metadata hash
-        let metadata_hash =
-            utils::bytes_to_string(&env, &utils::hash_metadata(&env, &cert_id, &metadata));
+        events::emit_template_updated(&env, &template_id, template.version, false, &caller);
 
-        // Update the certificate metadata
-        certificate.metadata.description = metadata;
-        certificate.metadata_hash = metadata_hash;
+        Ok(())
+    }
+
+    /// Get the latest version of a certificate template
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `template_id` - The unique identifier for the template
+    ///
+    /// # Returns
+    /// * `Result<CertificateTemplate, Error>` - The template or an error
+    pub fn get_template(env: Env, template_id: String) -> Result<CertificateTemplate, Error> {
+        storage::get_template(&env, &template_id).ok_or(Error::TemplateNotFound)
+    }
+
+    /// Get a certificate template as it was at a given version
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `template_id` - The unique identifier for the template
+    /// * `version` - The version to read, starting at 1
+    ///
+    /// # Returns
+    /// * `Result<CertificateTemplate, Error>` - The template version, or
+    ///   `InvalidParameter` if the template has no such version
+    pub fn get_template_version(
+        env: Env,
+        template_id: String,
+        version: u32,
+    ) -> Result<CertificateTemplate, Error> {
+        if storage::get_template(&env, &template_id).is_none() {
+            return Err(Error::TemplateNotFound);
+        }
+
+        storage::get_template_version(&env, &template_id, version).ok_or(Error::InvalidParameter)
+    }
+
+    /// Issue a certificate from a template
+    ///
+    /// The title, type and expiry come from the template, and the description
+    /// is the template's pattern filled in with `field_values`.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The issuer of the certificate (ISSUER or ADMIN)
+    /// * `template_id` - The unique identifier for the template
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `owner` - The address of the certificate owner
+    /// * `field_values` - The custom fields of the certificate
+    /// * `signature` - Digital signature of the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn issue_from_template(
+        env: Env,
+        caller: Address,
+        template_id: String,
+        cert_id: String,
+        owner: Address,
+        field_values: Map<String, String>,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        // Check if caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        let template = storage::get_template(&env, &template_id).ok_or(Error::TemplateNotFound)?;
+        if !template.is_active {
+            return Err(Error::TemplateInactive);
+        }
+
+        // Every required field must be given
+        for field in template.required_custom_fields.iter() {
+            if !field_values.contains_key(field) {
+                return Err(Error::InvalidMetadata);
+            }
+        }
+
+        let description =
+            utils::Utils::render_pattern(&env, &template.description_pattern, &field_values)?;
+        let expiration_date = match template.default_validity_secs {
+            0 => 0,
+            validity => env.ledger().timestamp().saturating_add(validity),
+        };
+        let metadata = Self::new_metadata(
+            &env,
+            &template.title,
+            description,
+            template.cert_type.clone(),
+            expiration_date,
+            field_values,
+        );
+
+        Self::issue(
+            &env,
+            &caller,
+            &cert_id,
+            &owner,
+            metadata,
+            &signature,
+            Some(&template),
+        )
+    }
+
+    /// Register a credential schema
+    ///
+    /// Schemas cannot be changed once registered, so that certificates keep
+    /// meaning what they meant when they were issued; register a new ID instead.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address registering the schema (must be ADMIN)
+    /// * `schema_id` - The unique identifier for the schema
+    /// * `fields` - The custom fields allowed by the schema
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn register_schema(
+        env: Env,
+        caller: Address,
+        schema_id: String,
+        fields: Vec<SchemaField>,
+    ) -> Result<(), Error> {
+        // Only the admin can manage schemas
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        if schema_id.is_empty() {
+            return Err(Error::InvalidParameter);
+        }
+        if storage::get_schema(&env, &schema_id).is_some() {
+            return Err(Error::SchemaAlreadyExists);
+        }
+
+        // Field names must be usable as custom field keys and appear once
+        let config = storage::Storage::get_config(&env);
+        if fields.len() > config.max_custom_fields {
+            return Err(Error::InvalidParameter);
+        }
+        for (i, field) in fields.iter().enumerate() {
+            if field.name.is_empty()
+                || field.name.len() > config.max_field_key_length
+                || fields
+                    .iter()
+                    .skip(i + 1)
+                    .any(|other| other.name == field.name)
+            {
+                return Err(Error::InvalidParameter);
+            }
+        }
+
+        storage::set_schema(
+            &env,
+            &CredentialSchema {
+                schema_id: schema_id.clone(),
+                fields,
+                created_by: caller.clone(),
+                created_at: env.ledger().timestamp(),
+            },
+        );
+
+        events::emit_schema_registered(&env, &schema_id, &caller);
+
+        Ok(())
+    }
+
+    /// Get a credential schema
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `schema_id` - The unique identifier for the schema
+    ///
+    /// # Returns
+    /// * `Result<CredentialSchema, Error>` - The schema or an error
+    pub fn get_schema(env: Env, schema_id: String) -> Result<CredentialSchema, Error> {
+        storage::get_schema(&env, &schema_id).ok_or(Error::SchemaNotFound)
+    }
+
+    /// Set the credential schema that new certificates of a type must conform to
+    ///
+    /// Certificates already issued keep the schema they were issued with.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the configuration (must be ADMIN)
+    /// * `cert_type` - The certificate type
+    /// * `schema_id` - The schema to use, or `None` to leave custom fields unstructured
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_type_schema(
+        env: Env,
+        caller: Address,
+        cert_type: CertificateType,
+        schema_id: Option<String>,
+    ) -> Result<(), Error> {
+        // Only the admin can change the configuration
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        let mut schemas = storage::Storage::get_type_schemas(&env);
+        match schema_id {
+            Some(schema_id) => {
+                if storage::get_schema(&env, &schema_id).is_none() {
+                    return Err(Error::SchemaNotFound);
+                }
+                schemas.set(cert_type, schema_id);
+            }
+            None => {
+                schemas.remove(cert_type);
+            }
+        }
+        storage::Storage::set_type_schemas(&env, &schemas);
+
+        Ok(())
+    }
+
+    /// Get the credential schema that new certificates of a type must conform to
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_type` - The certificate type
+    ///
+    /// # Returns
+    /// * `Option<String>` - The schema ID, if the type has one
+    pub fn get_type_schema(env: Env, cert_type: CertificateType) -> Option<String> {
+        storage::Storage::get_type_schemas(&env).get(cert_type)
+    }
+
+    /// Find the custom field that keeps a certificate of a type from conforming to its schema
+    ///
+    /// Issuance only reports a schema violation as `InvalidMetadata`, and the
+    /// `SCHEMA_VIOLATION` event naming the field is rolled back with the failed
+    /// call, so issuers can call this first to learn which field to fix.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_type` - The certificate type
+    /// * `custom_fields` - The custom fields to check
+    ///
+    /// # Returns
+    /// * `Result<Option<String>, Error>` - The offending field, or `None` if the
+    ///   fields conform or the type has no schema
+    pub fn validate_fields(
+        env: Env,
+        cert_type: CertificateType,
+        custom_fields: Map<String, String>,
+    ) -> Result<Option<String>, Error> {
+        let Some(schema_id) = storage::Storage::get_type_schemas(&env).get(cert_type) else {
+            return Ok(None);
+        };
+        let schema = storage::get_schema(&env, &schema_id).ok_or(Error::SchemaNotFound)?;
+
+        Ok(utils::Utils::schema_violation(&schema, &custom_fields))
+    }
+
+    /// Propose a certificate of a type that needs approval from several issuers
+    ///
+    /// The proposal counts as the proposer's approval. The certificate is
+    /// issued, with the proposer as its issuer, once enough distinct issuers
+    /// have approved it through `approve_certificate`. The proposer's quota
+    /// and issuance fee are settled at that point: the proposal only grants
+    /// the contract an allowance for the fee, so an expired draft costs nothing.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The issuer proposing the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `owner` - The address of the certificate owner
+    /// * `metadata` - The certificate metadata
+    /// * `signature` - Digital signature of the certificate
+    /// * `cert_type` - The type of the certificate
+    /// * `expiration_date` - The expiration date of the certificate
+    /// * `custom_fields` - Additional key-value fields, such as grade or cohort
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn propose_certificate(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        owner: Address,
+        metadata: String,
+        signature: BytesN<64>,
+        cert_type: CertificateType,
+        expiration_date: u64,
+        custom_fields: Map<String, String>,
+    ) -> Result<(), Error> {
+        // Check if caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check the proposer is a registered and active certification authority
+        let authority = storage::get_authority(&env, &caller).ok_or(Error::AuthorityNotFound)?;
+        if !authority.is_active {
+            return Err(Error::AuthorityInactive);
+        }
+
+        // Check the ID is free; expired drafts can be proposed again
+        let now = env.ledger().timestamp();
+        if storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateAlreadyExists);
+        }
+        if let Some(draft) = storage::get_draft(&env, &cert_id) {
+            if now <= draft.expires_at {
+                return Err(Error::CertificateAlreadyExists);
+            }
+        }
+
+        // Check the custom fields against the configured limits and the type's schema
+        let config = storage::Storage::get_config(&env);
+        utils::Utils::validate_custom_fields(&config, &custom_fields)?;
+        Self::check_schema(
+            &env,
+            &cert_id,
+            &storage::Storage::get_type_schemas(&env).get(cert_type.clone()),
+            &custom_fields,
+        )?;
+
+        // Check the signature with the proposer's key before collecting approvals
+        utils::Utils::verify_signature(
+            &env,
+            &utils::signing_digest(
+                &env,
+                &cert_id,
+                &Self::new_metadata(
+                    &env,
+                    &cert_id,
+                    metadata.clone(),
+                    cert_type.clone(),
+                    expiration_date,
+                    custom_fields.clone(),
+                ),
+                false,
+            ),
+            &signature,
+            &authority.verification_key,
+        );
+
+        // Fail early on a used up quota, although it is only counted at issuance
+        Self::check_quota(&env, &caller, &cert_type, 1)?;
+
+        // The proposer may not sign the final approval, so it lets the contract collect the fee then
+        Self::approve_draft_fee(&env, &caller, &cert_type)?;
+
+        let draft = CertificateDraft {
+            owner,
+            proposer: caller.clone(),
+            description: metadata,
+            signature,
+            cert_type: cert_type.clone(),
+            expiration_date,
+            custom_fields,
+            threshold: Self::approval_threshold(&env, &cert_type),
+            approvals: soroban_sdk::vec![&env, caller.clone()],
+            expires_at: now.saturating_add(config.approval_window),
+        };
+        events::emit_certificate_proposed(&env, &cert_id, &caller, &cert_type, draft.expires_at);
+
+        Self::store_or_issue_draft(&env, &cert_id, draft)
+    }
+
+    /// Approve a certificate draft, issuing the certificate once the threshold is met
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The issuer approving the draft (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn approve_certificate(env: Env, caller: Address, cert_id: String) -> Result<(), Error> {
+        // Check if caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        let mut draft = storage::get_draft(&env, &cert_id).ok_or(Error::DraftNotFound)?;
+        if env.ledger().timestamp() > draft.expires_at {
+            return Err(Error::DraftExpired);
+        }
+
+        // Each issuer counts once
+        if draft.approvals.contains(&caller) {
+            return Err(Error::AlreadyApproved);
+        }
+        draft.approvals.push_back(caller.clone());
+        events::emit_certificate_approved(
+            &env,
+            &cert_id,
+            &caller,
+            draft.approvals.len(),
+            draft.threshold,
+        );
+
+        Self::store_or_issue_draft(&env, &cert_id, draft)
+    }
+
+    /// Get a certificate draft waiting for approvals
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<CertificateDraft, Error>` - The draft, or `DraftNotFound`
+    pub fn get_draft(env: Env, cert_id: String) -> Result<CertificateDraft, Error> {
+        storage::get_draft(&env, &cert_id).ok_or(Error::DraftNotFound)
+    }
+
+    /// Set the number of distinct issuers that must approve certificates of a type
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the configuration (must be ADMIN)
+    /// * `cert_type` - The certificate type
+    /// * `threshold` - Number of approvals needed (1 or less to let a single issuer issue)
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_approval_threshold(
+        env: Env,
+        caller: Address,
+        cert_type: CertificateType,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        // Only the admin can change the configuration
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        let mut thresholds = storage::Storage::get_approval_thresholds(&env);
+        if threshold <= 1 {
+            thresholds.remove(cert_type);
+        } else {
+            thresholds.set(cert_type, threshold);
+        }
+        storage::Storage::set_approval_thresholds(&env, &thresholds);
+
+        Ok(())
+    }
+
+    /// Get the number of distinct issuers that must approve certificates of a type
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_type` - The certificate type
+    ///
+    /// # Returns
+    /// * `u32` - The number of approvals needed (1 when a single issuer can issue)
+    pub fn get_approval_threshold(env: Env, cert_type: CertificateType) -> u32 {
+        Self::approval_threshold(&env, &cert_type)
+    }
+
+    /// Set how long certificate drafts stay open for approvals
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the configuration (must be ADMIN)
+    /// * `approval_window` - Time, in seconds, from proposal to expiry
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_approval_window(
+        env: Env,
+        caller: Address,
+        approval_window: u64,
+    ) -> Result<(), Error> {
+        // Only the admin can change the configuration
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        if approval_window == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut config = storage::Storage::get_config(&env);
+        config.approval_window = approval_window;
+        storage::Storage::set_config(&env, &config);
+
+        Ok(())
+    }
+
+    /// Set the issuance quota of an issuer
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the quota (must be ADMIN)
+    /// * `issuer` - The issuer the quota applies to
+    /// * `quota` - The issuance limits, where 0 means unlimited
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_issuer_quota(
+        env: Env,
+        caller: Address,
+        issuer: Address,
+        quota: IssuerQuota,
+    ) -> Result<(), Error> {
+        // Only the admin can set quotas
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        // A per-period limit needs a period to count over
+        if quota.period_limit != 0 && quota.period_length == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        storage::set_issuer_quota(&env, &issuer, &quota);
+
+        Ok(())
+    }
+
+    /// Remove the issuance quota of an issuer
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address removing the quota (must be ADMIN)
+    /// * `issuer` - The issuer the quota applies to
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn remove_issuer_quota(env: Env, caller: Address, issuer: Address) -> Result<(), Error> {
+        // Only the admin can remove quotas
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        storage::remove_issuer_quota(&env, &issuer);
+
+        Ok(())
+    }
+
+    /// Get the issuance quota of an issuer
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `issuer` - The issuer address
+    ///
+    /// # Returns
+    /// * `Option<IssuerQuota>` - The quota, or `None` if the issuer is unlimited
+    pub fn get_issuer_quota(env: Env, issuer: Address) -> Option<IssuerQuota> {
+        storage::get_issuer_quota(&env, &issuer)
+    }
+
+    /// Get the number of certificates an issuer has issued
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `issuer` - The issuer address
+    ///
+    /// # Returns
+    /// * `IssuerUsage` - The issuer's counts, tracked whether or not it has a quota
+    pub fn get_issuer_usage(env: Env, issuer: Address) -> IssuerUsage {
+        storage::get_issuer_usage(&env, &issuer)
+    }
+
+    /// Set the token issuance fees are paid in and the treasury that receives them
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the configuration (must be ADMIN)
+    /// * `token` - The token contract, or `None` to disable fees
+    /// * `treasury` - The address that receives fees, or `None` to disable fees
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_fee_config(
+        env: Env,
+        caller: Address,
+        token: Option<Address>,
+        treasury: Option<Address>,
+    ) -> Result<(), Error> {
+        // Only the admin can change the configuration
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        // Fees need both a token and a treasury, or neither
+        if token.is_some() != treasury.is_some() {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut config = storage::Storage::get_config(&env);
+        config.fee_token = token;
+        config.fee_treasury = treasury;
+        storage::Storage::set_config(&env, &config);
+
+        Ok(())
+    }
+
+    /// Set the issuance fee of a certificate type
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the configuration (must be ADMIN)
+    /// * `cert_type` - The certificate type
+    /// * `amount` - The fee, in the token's smallest unit (0 for no fee)
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_issuance_fee(
+        env: Env,
+        caller: Address,
+        cert_type: CertificateType,
+        amount: i128,
+    ) -> Result<(), Error> {
+        // Only the admin can change the configuration
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        if amount < 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut config = storage::Storage::get_config(&env);
+        if amount == 0 {
+            config.issuance_fees.remove(cert_type);
+        } else {
+            config.issuance_fees.set(cert_type, amount);
+        }
+        storage::Storage::set_config(&env, &config);
+
+        Ok(())
+    }
+
+    /// Get the issuance fee of a certificate type
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_type` - The certificate type
+    ///
+    /// # Returns
+    /// * `i128` - The fee, or 0 if the type is free or fees are disabled
+    pub fn get_issuance_fee(env: Env, cert_type: CertificateType) -> i128 {
+        let config = storage::Storage::get_config(&env);
+        if config.fee_token.is_none() {
+            return 0;
+        }
+
+        config.issuance_fees.get(cert_type).unwrap_or(0)
+    }
+
+    /// Exempt an issuer from issuance fees, or end its exemption
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the exemption (must be ADMIN)
+    /// * `issuer` - The issuer address
+    /// * `exempt` - Whether the issuer is exempt
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_fee_exempt(
+        env: Env,
+        caller: Address,
+        issuer: Address,
+        exempt: bool,
+    ) -> Result<(), Error> {
+        // Only the admin can change exemptions
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        storage::set_fee_exempt(&env, &issuer, exempt);
+
+        Ok(())
+    }
+
+    /// Check whether an issuer is exempt from issuance fees
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `issuer` - The issuer address
+    ///
+    /// # Returns
+    /// * `bool` - True if the issuer is exempt
+    pub fn is_fee_exempt(env: Env, issuer: Address) -> bool {
+        storage::is_fee_exempt(&env, &issuer)
+    }
+
+    /// Batch issue multiple certificates
+    ///
+    /// Entries that cannot be issued are skipped and reported, except for
+    /// signatures: every signature is checked before anything is issued, and
+    /// the host aborts the whole batch on one that does not verify.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The issuer of the certificates (ISSUER or ADMIN)
+    /// * `cert_ids` - The unique identifiers for the certificates
+    /// * `owners` - The addresses of the certificate owners
+    /// * `metadatas` - The certificate metadatas
+    /// * `signatures` - Digital signatures of the certificates
+    /// * `cert_types` - The types of the certificates
+    /// * `expiration_dates` - The expiration dates of the certificates
+    /// * `custom_fields` - The custom fields of the certificates
+    ///
+    /// # Returns
+    /// * `Result<Vec<String>, Error>` - Failed certificate IDs
+    pub fn batch_issue_certificates(
+        env: Env,
+        caller: Address,
+        cert_ids: Vec<String>,
+        owners: Vec<Address>,
+        metadatas: Vec<String>,
+        signatures: Vec<BytesN<64>>,
+        cert_types: Vec<CertificateType>,
+        expiration_dates: Vec<u64>,
+        custom_fields: Vec<Map<String, String>>,
+    ) -> Result<Vec<String>, Error> {
+        // Check if caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check if all vectors have the same length
+        let count = cert_ids.len();
+        if owners.len() != count
+            || metadatas.len() != count
+            || signatures.len() != count
+            || cert_types.len() != count
+            || expiration_dates.len() != count
+            || custom_fields.len() != count
+        {
+            return Err(Error::InvalidMetadata);
+        }
+
+        // Check every signature up front, so a forged or stale one rejects the
+        // batch before any certificate is issued
+        let authority = storage::get_authority(&env, &caller);
+        let mut metadata_list = Vec::new(&env);
+
+        for i in 0..count {
+            let cert_id = cert_ids.get(i).unwrap();
+            let metadata = Self::new_metadata(
+                &env,
+                &cert_id,
+                metadatas.get(i).unwrap(),
+                cert_types.get(i).unwrap(),
+                expiration_dates.get(i).unwrap(),
+                custom_fields.get(i).unwrap(),
+            );
+            if let Some(authority) = &authority {
+                utils::Utils::verify_signature(
+                    &env,
+                    &utils::signing_digest(&env, &cert_id, &metadata, false),
+                    &signatures.get(i).unwrap(),
+                    &authority.verification_key,
+                );
+            }
+            metadata_list.push_back(metadata);
+        }
+
+        // Process each certificate
+        let mut failed_certs = Vec::new(&env);
+
+        for i in 0..count {
+            let cert_id = cert_ids.get(i).unwrap();
+            let owner = owners.get(i).unwrap();
+            let metadata = metadata_list.get(i).unwrap();
+            let signature = signatures.get(i).unwrap();
+
+            // Try to issue the certificate
+            let result = Self::issue(&env, &caller, &cert_id, &owner, metadata, &signature, None);
+
+            // If failed, add to the failed list
+            if result.is_err() {
+                failed_certs.push_back(cert_id.clone());
+            }
+        }
+
+        Ok(failed_certs)
+    }
+
+    /// Anchor a cohort of certificates as a single Merkle root
+    ///
+    /// Members are not stored individually; each holder keeps the fields of
+    /// their leaf and a proof, checked with `verify_batch_membership`. The
+    /// batch counts as `count` certificates against quotas and fees.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The issuer of the batch (ISSUER or ADMIN)
+    /// * `batch_id` - The unique identifier for the batch
+    /// * `merkle_root` - Merkle root over the leaves built with `Utils::batch_leaf`
+    /// * `count` - The number of members in the batch
+    /// * `cert_type` - The type of the members' certificates
+    /// * `expiration_date` - The expiration date of the members' certificates
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn anchor_batch(
+        env: Env,
+        caller: Address,
+        batch_id: String,
+        merkle_root: BytesN<32>,
+        count: u32,
+        cert_type: CertificateType,
+        expiration_date: u64,
+    ) -> Result<(), Error> {
+        // Check if caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check the issuer is a registered and active certification authority
+        let authority = storage::get_authority(&env, &caller).ok_or(Error::AuthorityNotFound)?;
+        if !authority.is_active {
+            return Err(Error::AuthorityInactive);
+        }
+
+        if batch_id.is_empty() || count == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        // A batch that has already expired would never be valid
+        if expiration_date != 0 && expiration_date <= env.ledger().timestamp() {
+            return Err(Error::InvalidParameter);
+        }
+        if storage::get_batch(&env, &batch_id).is_some() {
+            return Err(Error::BatchAlreadyExists);
+        }
+
+        // Types that need several approvals cannot be issued by one issuer in bulk
+        if Self::approval_threshold(&env, &cert_type) > 1 {
+            return Err(Error::ApprovalRequired);
+        }
+
+        // Check the issuer's quota, then collect the fees; nothing after this can fail
+        let usage = Self::check_quota(&env, &caller, &cert_type, count)?;
+        Self::charge_fee(&env, &caller, &cert_type, count)?;
+        storage::set_issuer_usage(&env, &caller, &usage);
+
+        storage::set_batch(
+            &env,
+            &CertificateBatch {
+                batch_id: batch_id.clone(),
+                issuer: caller.clone(),
+                merkle_root: merkle_root.clone(),
+                count,
+                cert_type: cert_type.clone(),
+                expiration_date,
+                anchored_at: env.ledger().timestamp(),
+                revoked_count: 0,
+            },
+        );
+
+        events::emit_batch_anchored(&env, &batch_id, &caller, &merkle_root, count, &cert_type);
+
+        Ok(())
+    }
+
+    /// Check that a certificate is a member of a batch and still valid
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `batch_id` - The unique identifier for the batch
+    /// * `leaf_fields` - The fields of the member's leaf
+    /// * `proof` - Sibling hashes from the member's leaf up to the root
+    ///
+    /// # Returns
+    /// * `Result<BatchVerificationResult, Error>` - The verification result, or
+    ///   `BatchNotFound` if the batch does not exist
+    pub fn verify_batch_membership(
+        env: Env,
+        batch_id: String,
+        leaf_fields: Map<String, String>,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<BatchVerificationResult, Error> {
+        let batch = storage::get_batch(&env, &batch_id).ok_or(Error::BatchNotFound)?;
+        if proof.len() > utils::MAX_PROOF_DEPTH {
+            return Err(Error::InvalidParameter);
+        }
+
+        let leaf_hash = utils::Utils::batch_leaf(&env, &batch_id, &leaf_fields);
+        let is_member =
+            utils::Utils::verify_merkle_proof(&env, &leaf_hash, &proof, &batch.merkle_root);
+        let is_revoked = storage::get_batch_revocation(&env, &batch_id, &leaf_hash).is_some();
+        let is_expired =
+            batch.expiration_date != 0 && env.ledger().timestamp() > batch.expiration_date;
+
+        Ok(BatchVerificationResult {
+            leaf_hash,
+            is_member,
+            is_revoked,
+            is_valid: is_member && !is_revoked && !is_expired,
+        })
+    }
+
+    /// Revoke a single member of a certificate batch
+    ///
+    /// The member is identified by its leaf hash and proven to be in the
+    /// batch with the same proof as `verify_batch_membership`, so only leaves
+    /// under the anchored root can be revoked, and at most `count` of them.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address revoking the member (REVOKER or ADMIN)
+    /// * `batch_id` - The unique identifier for the batch
+    /// * `leaf_hash` - The leaf hash of the member
+    /// * `proof` - Sibling hashes from the member's leaf up to the root
+    /// * `reason` - Machine-readable reason for the revocation
+    /// * `details` - Optional free-text details
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn revoke_batch_member(
+        env: Env,
+        caller: Address,
+        batch_id: String,
+        leaf_hash: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+        reason: RevocationReason,
+        details: Option<String>,
+    ) -> Result<(), Error> {
+        // Check if the caller is authorized to revoke certificates
+        access_control::AccessControl::require_revoker(&env, &caller)?;
+
+        let mut batch = storage::get_batch(&env, &batch_id).ok_or(Error::BatchNotFound)?;
+
+        // Only members of the batch can be revoked
+        if proof.len() > utils::MAX_PROOF_DEPTH {
+            return Err(Error::InvalidParameter);
+        }
+        if !utils::Utils::verify_merkle_proof(&env, &leaf_hash, &proof, &batch.merkle_root) {
+            return Err(Error::VerificationFailed);
+        }
+
+        // A member can only be revoked once, and no more members than were anchored
+        if storage::get_batch_revocation(&env, &batch_id, &leaf_hash).is_some() {
+            return Err(Error::CertificateRevoked);
+        }
+        if batch.revoked_count >= batch.count {
+            return Err(Error::InvalidParameter);
+        }
+
+        storage::set_batch_revocation(
+            &env,
+            &batch_id,
+            &leaf_hash,
+            &RevocationRecord {
+                reason: reason.clone(),
+                details,
+                revoked_at: env.ledger().timestamp(),
+                revoked_by: caller.clone(),
+            },
+        );
+        batch.revoked_count += 1;
+        storage::set_batch(&env, &batch);
+
+        events::emit_batch_member_revoked(&env, &batch_id, &leaf_hash, &reason, &caller);
+
+        Ok(())
+    }
+
+    /// Get a certificate batch
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `batch_id` - The unique identifier for the batch
+    ///
+    /// # Returns
+    /// * `Result<CertificateBatch, Error>` - The batch, or `BatchNotFound`
+    pub fn get_batch(env: Env, batch_id: String) -> Result<CertificateBatch, Error> {
+        storage::get_batch(&env, &batch_id).ok_or(Error::BatchNotFound)
+    }
+
+    /// Get the details of a certificate
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<CertificateDetails, Error>` - The certificate details or an error
+    pub fn get_certificate_details(env: Env, cert_id: String) -> Result<CertificateDetails, Error> {
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        // Report the effective status, so expired certificates read as Expired
+        let mut certificate = storage::get_certificate(&env, &cert_id);
+        certificate.status = utils::Utils::effective_status(&env, &certificate);
+
+        Ok(certificate)
+    }
+
+    /// Extend the TTL of a certificate to the configured maximum
+    ///
+    /// Anyone, such as the holder or a verifier, can pay to keep a
+    /// certificate in live state, together with its revocation details,
+    /// suspension history, version history and owner index entries.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn extend_certificate_ttl(env: Env, cert_id: String) -> Result<(), Error> {
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        storage::extend_certificate_ttl(&env, &cert_id);
+
+        Ok(())
+    }
+
+    /// Verify a certificate by checking its status, metadata hash and signature
+    ///
+    /// Signatures are verified against the issuing authority's key over the
+    /// raw signing digest when a certificate is issued or amended, and a bad
+    /// signature rejects the write; they are not checked again here.
+    /// `signature_checked_on_write` reports whether the stored signature went
+    /// through that check, and a certificate is only valid if it did and its
+    /// issuing authority is still active.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `metadata_hash` - The hash to verify against the stored metadata hash
+    ///
+    /// # Returns
+    /// * `Result<VerificationResult, Error>` - Detailed verification result, or
+    ///   `CertificateNotFound` if the certificate does not exist
+    pub fn verify_certificate(
+        env: Env,
+        cert_id: String,
+        metadata_hash: String,
+    ) -> Result<VerificationResult, Error> {
+        // Get the certificate details
+        let certificate = Self::get_certificate_details(env.clone(), cert_id.clone())?;
+
+        // Signatures are checked when they are written, so only the outcome is read back
+        let signature_checked_on_write = certificate.signing_key.is_some();
+        let authority_active = storage::get_authority(&env, &certificate.issuer)
+            .is_some_and(|authority| authority.is_active);
+
+        Ok(utils::Utils::create_verification_result(
+            &env,
+            &certificate,
+            &metadata_hash,
+            signature_checked_on_write,
+            authority_active,
+        ))
+    }
+
+    /// Verify a single field of a certificate issued with `issue_private_certificate`
+    ///
+    /// Only the proof is checked; use `verify_certificate` for the status and
+    /// signature. Call this through a simulation rather than a transaction to
+    /// keep the disclosed value off the ledger.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `field_name` - The name of the disclosed field
+    /// * `value` - The value of the disclosed field
+    /// * `salt` - The salt of the field's leaf
+    /// * `proof` - Sibling hashes from the field's leaf up to the root
+    ///
+    /// # Returns
+    /// * `Result<bool, Error>` - Whether the field is committed to by the
+    ///   certificate, or `OperationNotSupported` if it has no fields root
+    pub fn verify_field_disclosure(
+        env: Env,
+        cert_id: String,
+        field_name: String,
+        value: String,
+        salt: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<bool, Error> {
+        let certificate = Self::get_certificate_details(env.clone(), cert_id)?;
+        let fields_root = certificate
+            .fields_root
+            .ok_or(Error::OperationNotSupported)?;
+
+        if proof.len() > utils::MAX_PROOF_DEPTH {
+            return Err(Error::InvalidParameter);
+        }
+
+        let leaf = utils::Utils::disclosure_leaf(&env, &field_name, &value, &salt);
+        Ok(utils::Utils::verify_merkle_proof(
+            &env,
+            &leaf,
+            &proof,
+            &fields_root,
+        ))
+    }
+
+    /// List certificates owned by a specific address
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `owner` - The address of the certificate owner
+    ///
+    /// # Returns
+    /// * `Vec<String>` - A list of certificate IDs owned by the address
+    pub fn list_certificates_by_owner(env: Env, owner: Address) -> Vec<String> {
+        let count = storage::get_owner_certificate_count(&env, &owner);
+        storage::get_owner_certificate_ids(&env, &owner, 0, count)
+    }
+
+    /// List a page of all issued certificates
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address listing certificates (ADMIN, ISSUER or VERIFIER)
+    /// * `start` - Position to start from (0 or the `next_cursor` of the previous page)
+    /// * `limit` - Maximum number of certificate IDs to return (capped at `MAX_PAGE_SIZE`)
+    ///
+    /// # Returns
+    /// * `Result<CertificatePage, Error>` - A page of certificate IDs, the next cursor and the total count
+    pub fn list_certificates(
+        env: Env,
+        caller: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<CertificatePage, Error> {
+        // Check if the caller is allowed to read the registry
+        access_control::AccessControl::require_verifier(&env, &caller)?;
+
+        let limit = utils::Utils::page_limit(limit)?;
+        let total = storage::Storage::get_certificate_count(&env);
+        let cert_ids = storage::get_certificate_ids(&env, start, limit);
+
+        Ok(CertificatePage {
+            next_cursor: utils::Utils::next_cursor(start, cert_ids.len(), total),
+            cert_ids,
+            total,
+        })
+    }
+
+    /// List a page of the certificates owned by a specific address
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `owner` - The address of the certificate owner
+    /// * `start` - Position to start from (0 or the `next_cursor` of the previous page)
+    /// * `limit` - Maximum number of certificate IDs to return (capped at `MAX_PAGE_SIZE`)
+    ///
+    /// # Returns
+    /// * `Result<CertificatePage, Error>` - A page of certificate IDs and the next cursor
+    pub fn list_certificates_by_owner_page(
+        env: Env,
+        owner: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<CertificatePage, Error> {
+        let limit = utils::Utils::page_limit(limit)?;
+        let total = storage::get_owner_certificate_count(&env, &owner);
+        let cert_ids = storage::get_owner_certificate_ids(&env, &owner, start, limit);
+
+        Ok(CertificatePage {
+            next_cursor: utils::Utils::next_cursor(start, cert_ids.len(), total),
+            cert_ids,
+            total,
+        })
+    }
+
+    /// Update the status of a certificate
+    ///
+    /// Revocations are handed to `revoke_certificate` with the `Other` reason
+    /// code, so they need the REVOKER role. Suspensions are handed to
+    /// `suspend_certificate` with no end date and reinstatements to
+    /// `reinstate_certificate`, so both are kept in the suspension history;
+    /// every other transition is rejected by the certificate lifecycle.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `status` - The new status for the certificate
+    /// * `reason` - Optional reason for the status change
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn update_certificate_status(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        status: CertificateStatus,
+        reason: Option<String>,
+    ) -> Result<(), Error> {
+        // Revocation stays gated by the REVOKER role and is recorded like any other
+        if status == CertificateStatus::Revoked {
+            return Self::revoke_certificate(env, caller, cert_id, RevocationReason::Other, reason);
+        }
+
+        // Check if the caller is authorized to update certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        let certificate = storage::get_certificate(&env, &cert_id);
+        let old_status = utils::Utils::effective_status(&env, &certificate);
+
+        if !utils::Utils::is_valid_transition(&old_status, &status) {
+            return Err(Error::InvalidCertificateStatus);
+        }
+
+        // Only suspending and reinstating are left, and both keep the history
+        if status == CertificateStatus::Suspended {
+            let reason = reason.unwrap_or_else(|| String::from_str(&env, ""));
+            Self::suspend(&env, &caller, &cert_id, 0, reason)
+        } else {
+            Self::reinstate(&env, &caller, &cert_id)
+        }
+    }
+
+    /// Revoke a certificate with a machine-readable reason
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address revoking the certificate (REVOKER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `reason` - The reason code for the revocation
+    /// * `details` - Optional free-text details
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn revoke_certificate(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        reason: RevocationReason,
+        details: Option<String>,
+    ) -> Result<(), Error> {
+        // Check if the caller is authorized to revoke certificates
+        access_control::AccessControl::require_revoker(&env, &caller)?;
+
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        // A certificate can only be revoked once
+        let mut certificate = storage::get_certificate(&env, &cert_id);
+        let old_status = utils::Utils::effective_status(&env, &certificate);
+        if old_status == CertificateStatus::Revoked {
+            return Err(Error::CertificateRevoked);
+        }
+
+        // Renounced certificates are tombstones and stay that way
+        if old_status == CertificateStatus::Renounced {
+            return Err(Error::CertificateRenounced);
+        }
+
+        // Record who revoked the certificate, when and why
+        let now = env.ledger().timestamp();
+        storage::set_revocation(
+            &env,
+            &cert_id,
+            &RevocationRecord {
+                reason: reason.clone(),
+                details: details.clone(),
+                revoked_at: now,
+                revoked_by: caller.clone(),
+            },
+        );
+
+        // Revoke the certificate
+        certificate.status = CertificateStatus::Revoked;
+        certificate.revocation_reason = details.clone();
+        certificate.suspended_until = 0;
+        certificate.last_updated = now;
+        storage::set_certificate(&env, &cert_id, &certificate);
+
+        events::emit_certificate_revoked(&env, &cert_id, &caller, &reason, &details);
+        events::emit_status_changed(
+            &env,
+            &cert_id,
+            &old_status,
+            &CertificateStatus::Revoked,
+            &caller,
+        );
+
+        Ok(())
+    }
+
+    /// Get the revocation details of a certificate
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<RevocationRecord, Error>` - The revocation details, or
+    ///   `InvalidCertificateStatus` if it was not revoked through `revoke_certificate`
+    pub fn get_revocation(env: Env, cert_id: String) -> Result<RevocationRecord, Error> {
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        storage::get_revocation(&env, &cert_id).ok_or(Error::InvalidCertificateStatus)
+    }
+
+    /// Suspend a certificate, optionally until a given time
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address suspending the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `until` - Timestamp at which the suspension lifts on its own (0 to last until reinstated)
+    /// * `reason` - Reason for the suspension
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn suspend_certificate(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        until: u64,
+        reason: String,
+    ) -> Result<(), Error> {
+        // Check if the caller is authorized to update certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        Self::suspend(&env, &caller, &cert_id, until, reason)
+    }
+
+    /// Lift the suspension of a certificate before it ends on its own
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address reinstating the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn reinstate_certificate(env: Env, caller: Address, cert_id: String) -> Result<(), Error> {
+        // Check if the caller is authorized to update certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        Self::reinstate(&env, &caller, &cert_id)
+    }
+
+    /// Get the suspension history of a certificate
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<Vec<SuspensionRecord>, Error>` - The suspensions, oldest first, or an error
+    pub fn get_suspension_history(
+        env: Env,
+        cert_id: String,
+    ) -> Result<Vec<SuspensionRecord>, Error> {
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        Ok(storage::get_suspension_history(&env, &cert_id))
+    }
+
+    /// Persist the Expired status of certificates past their expiration date
+    ///
+    /// Walks the issuance index from `cursor`, so anyone can sweep the whole
+    /// registry over several calls. Suspensions past their `until` time are
+    /// persisted as lifted in the same pass. Each transition emits a status
+    /// changed event.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cursor` - Position to start from (0 or the `next_cursor` of the previous sweep)
+    /// * `limit` - Maximum number of certificates to inspect (capped at `MAX_PAGE_SIZE`)
+    ///
+    /// # Returns
+    /// * `Result<CertificatePage, Error>` - The IDs whose status changed, the next cursor and the registry size
+    pub fn sweep_expired(env: Env, cursor: u32, limit: u32) -> Result<CertificatePage, Error> {
+        let limit = utils::Utils::page_limit(limit)?;
+        let total = storage::Storage::get_certificate_count(&env);
+        let inspected = storage::get_certificate_ids(&env, cursor, limit);
+        let sweeper = env.current_contract_address();
+        let mut expired = Vec::new(&env);
+
+        for cert_id in inspected.iter() {
+            let mut certificate = storage::get_certificate(&env, &cert_id);
+            let status = utils::Utils::effective_status(&env, &certificate);
+            if status == certificate.status {
+                continue;
+            }
+
+            // Persist the transition and let indexers know about it
+            let old_status = certificate.status.clone();
+            certificate.status = status.clone();
+            certificate.suspended_until = 0;
+            certificate.last_updated = env.ledger().timestamp();
+            storage::set_certificate(&env, &cert_id, &certificate);
+            events::emit_status_changed(&env, &cert_id, &old_status, &status, &sweeper);
+
+            expired.push_back(cert_id);
+        }
+
+        Ok(CertificatePage {
+            next_cursor: utils::Utils::next_cursor(cursor, inspected.len(), total),
+            cert_ids: expired,
+            total,
+        })
+    }
+
+    /// Update certificate metadata
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `metadata` - The new metadata for the certificate
+    /// * `signature` - Digital signature of the updated certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn update_certificate_metadata(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        metadata: String,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        let mut certificate = Self::load_for_update(&env, &caller, &cert_id)?;
+
+        // Update the certificate description
+        let old_hash = certificate.metadata_hash.clone();
+        certificate.metadata.description = metadata;
+
+        Self::commit_update(&env, &caller, &cert_id, certificate, old_hash, signature)
+    }
+
+    /// Set a custom field on a certificate, adding or replacing it
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `key` - The field name
+    /// * `value` - The field value
+    /// * `signature` - Digital signature of the updated certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_custom_field(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        key: String,
+        value: String,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        let mut certificate = Self::load_for_update(&env, &caller, &cert_id)?;
+        let old_hash = certificate.metadata_hash.clone();
+
+        // Set the field and check the result against the configured limits
+        certificate.metadata.custom_fields.set(key, value);
+        utils::Utils::validate_custom_fields(
+            &storage::Storage::get_config(&env),
+            &certificate.metadata.custom_fields,
+        )?;
+        Self::check_schema(
+            &env,
+            &cert_id,
+            &certificate.schema_id,
+            &certificate.metadata.custom_fields,
+        )?;
+
+        Self::commit_update(&env, &caller, &cert_id, certificate, old_hash, signature)
+    }
+
+    /// Remove a custom field from a certificate
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `key` - The field name
+    /// * `signature` - Digital signature of the updated certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn remove_custom_field(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        key: String,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        let mut certificate = Self::load_for_update(&env, &caller, &cert_id)?;
+        let old_hash = certificate.metadata_hash.clone();
+
+        // Check the field exists
+        if certificate.metadata.custom_fields.remove(key).is_none() {
+            return Err(Error::InvalidParameter);
+        }
+
+        // Required schema fields cannot be removed
+        Self::check_schema(
+            &env,
+            &cert_id,
+            &certificate.schema_id,
+            &certificate.metadata.custom_fields,
+        )?;
+
+        Self::commit_update(&env, &caller, &cert_id, certificate, old_hash, signature)
+    }
+
+    /// Set the limits applied to custom fields
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the configuration (must be ADMIN)
+    /// * `max_custom_fields` - Maximum number of custom fields per certificate
+    /// * `max_field_key_length` - Maximum length, in bytes, of a field key
+    /// * `max_field_value_length` - Maximum length, in bytes, of a field value
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_custom_field_limits(
+        env: Env,
+        caller: Address,
+        max_custom_fields: u32,
+        max_field_key_length: u32,
+        max_field_value_length: u32,
+    ) -> Result<(), Error> {
+        // Only the admin can change the configuration
+        access_control::AccessControl::require_admin(&env, &caller)?;
+
+        // Keys must be allowed at least one character
+        if max_field_key_length == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut config = storage::Storage::get_config(&env);
+        config.max_custom_fields = max_custom_fields;
+        config.max_field_key_length = max_field_key_length;
+        config.max_field_value_length = max_field_value_length;
+        storage::Storage::set_config(&env, &config);
+
+        Ok(())
+    }
+
+    /// Allow or forbid the owner of a certificate to transfer it
+    ///
+    /// Certificates are soulbound when issued.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the certificate (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `transferable` - Whether the owner may transfer the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn set_transferable(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        transferable: bool,
+    ) -> Result<(), Error> {
+        // Check if the caller is authorized to update certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        let mut certificate = storage::get_certificate(&env, &cert_id);
+        certificate.transferable = transferable;
+        certificate.last_updated = env.ledger().timestamp();
+        storage::set_certificate(&env, &cert_id, &certificate);
+
+        Ok(())
+    }
+
+    /// Transfer a transferable certificate to a new owner
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `from` - The current owner of the certificate
+    /// * `to` - The address of the new owner
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn transfer_certificate(
+        env: Env,
+        cert_id: String,
+        from: Address,
+        to: Address,
+    ) -> Result<(), Error> {
+        // Require authorization from the current owner
+        from.require_auth();
+
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        let mut certificate = storage::get_certificate(&env, &cert_id);
+        if certificate.owner != from {
+            return Err(Error::Unauthorized);
+        }
+        if !certificate.transferable {
+            return Err(Error::CertificateNotTransferable);
+        }
+        if to == from {
+            return Err(Error::InvalidParameter);
+        }
+
+        // Only active certificates change hands
+        match utils::Utils::effective_status(&env, &certificate) {
+            CertificateStatus::Active => {}
+            CertificateStatus::Revoked => return Err(Error::CertificateRevoked),
+            CertificateStatus::Expired => return Err(Error::CertificateExpired),
+            CertificateStatus::Suspended => return Err(Error::CertificateSuspended),
+            CertificateStatus::Renounced => return Err(Error::CertificateRenounced),
+        }
+
+        Self::move_to_owner(&env, &cert_id, &mut certificate, &to);
+        events::emit_certificate_transferred(&env, &cert_id, &from, &to);
+
+        Ok(())
+    }
+
+    /// Move a certificate to a new owner on the issuer's approval, such as
+    /// when the holder of a soulbound certificate migrates to a new wallet
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address approving the move (ISSUER or ADMIN)
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `new_owner` - The address of the new owner
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn reassign_owner(
+        env: Env,
+        caller: Address,
+        cert_id: String,
+        new_owner: Address,
+    ) -> Result<(), Error> {
+        // Check if the caller is authorized to update certificates
+        access_control::AccessControl::require_issuer(&env, &caller)?;
+
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        let mut certificate = storage::get_certificate(&env, &cert_id);
+        let old_owner = certificate.owner.clone();
+        if new_owner == old_owner {
+            return Err(Error::InvalidParameter);
+        }
+        if certificate.status == CertificateStatus::Renounced {
+            return Err(Error::CertificateRenounced);
+        }
+
+        Self::move_to_owner(&env, &cert_id, &mut certificate, &new_owner);
+        events::emit_owner_reassigned(&env, &cert_id, &old_owner, &new_owner, &caller);
+
+        Ok(())
+    }
+
+    /// Renounce a certificate, removing it from the owner's certificates
+    ///
+    /// The certificate is kept as a tombstone so that its ID cannot be issued again.
+    /// Revoked certificates cannot be renounced, so the revocation stays on record.
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn renounce_certificate(env: Env, cert_id: String) -> Result<(), Error> {
+        // Check if certificate exists
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        // Require authorization from the owner
+        let mut certificate = storage::get_certificate(&env, &cert_id);
+        let owner = certificate.owner.clone();
+        owner.require_auth();
+
+        // Revoked and Renounced are terminal, so there is nothing left to renounce
+        let old_status = utils::Utils::effective_status(&env, &certificate);
+        match old_status {
+            CertificateStatus::Revoked => return Err(Error::CertificateRevoked),
+            CertificateStatus::Renounced => return Err(Error::CertificateRenounced),
+            _ => {}
+        }
+
+        // Drop the certificate from the owner's index and mark it renounced
+        storage::remove_owner_certificate(&env, &owner, &cert_id);
+        certificate.status = CertificateStatus::Renounced;
+        certificate.suspended_until = 0;
+        certificate.last_updated = env.ledger().timestamp();
+        storage::set_certificate(&env, &cert_id, &certificate);
+
+        events::emit_certificate_renounced(&env, &cert_id, &owner);
+        events::emit_status_changed(
+            &env,
+            &cert_id,
+            &old_status,
+            &CertificateStatus::Renounced,
+            &owner,
+        );
+
+        Ok(())
+    }
+
+    /// Get a certificate as it was at a given version
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `version` - The version to read, starting at 1
+    ///
+    /// # Returns
+    /// * `Result<CertificateVersion, Error>` - The version snapshot, or
+    ///   `InvalidParameter` if the certificate has no such version
+    pub fn get_certificate_version(
+        env: Env,
+        cert_id: String,
+        version: u32,
+    ) -> Result<CertificateVersion, Error> {
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        storage::get_certificate_version(&env, &cert_id, version).ok_or(Error::InvalidParameter)
+    }
+
+    /// List the versions of a certificate, oldest first
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `cert_id` - The unique identifier for the certificate
+    /// * `start` - Position to start from (0 or the `next_cursor` of the previous page)
+    /// * `limit` - Maximum number of versions to return (capped at `MAX_PAGE_SIZE`)
+    ///
+    /// # Returns
+    /// * `Result<CertificateHistoryPage, Error>` - A page of versions, the next cursor and the
+    ///   number of versions
+    pub fn get_certificate_history(
+        env: Env,
+        cert_id: String,
+        start: u32,
+        limit: u32,
+    ) -> Result<CertificateHistoryPage, Error> {
+        if !storage::has_certificate(&env, &cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        let limit = utils::Utils::page_limit(limit)?;
+        let total = storage::get_certificate(&env, &cert_id).version;
+        let end = total.min(start.saturating_add(limit));
+        let mut versions = Vec::new(&env);
+
+        // Version numbers start at 1, positions at 0
+        for position in start..end {
+            if let Some(snapshot) = storage::get_certificate_version(&env, &cert_id, position + 1) {
+                versions.push_back(snapshot);
+            }
+        }
+
+        Ok(CertificateHistoryPage {
+            next_cursor: utils::Utils::next_cursor(start, end.saturating_sub(start), total),
+            versions,
+            total,
+        })
+    }
+
+    /// Register a new certification authority
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address registering the authority (AUTH_MANAGER or ADMIN)
+    /// * `authority` - The address of the authority
+    /// * `name` - The name of the authority
+    /// * `verification_key` - The ed25519 public key used to verify certificate signatures
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn register_authority(
+        env: Env,
+        caller: Address,
+        authority: Address,
+        name: String,
+        verification_key: BytesN<32>,
+    ) -> Result<(), Error> {
+        access_control::AccessControl::require_auth_manager(&env, &caller)?;
+
+        // Check if the authority already exists
+        if storage::has_authority(&env, &authority) {
+            return Err(Error::AuthorityAlreadyExists);
+        }
+
+        if name.is_empty() {
+            return Err(Error::InvalidParameter);
+        }
+
+        // Store the authority
+        storage::set_authority(
+            &env,
+            &CertificationAuthority {
+                name,
+                address: authority.clone(),
+                verification_key,
+                is_active: true,
+            },
+        );
+
+        // Emit authority added event
+        events::emit_authority_added(&env, &authority, &caller);
+
+        Ok(())
+    }
+
+    /// Update an existing certification authority
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address updating the authority (AUTH_MANAGER or ADMIN)
+    /// * `authority` - The address of the authority
+    /// * `name` - The new name of the authority
+    /// * `verification_key` - The new ed25519 public key
+    /// * `is_active` - Whether the authority is active
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn update_authority(
+        env: Env,
+        caller: Address,
+        authority: Address,
+        name: String,
+        verification_key: BytesN<32>,
+        is_active: bool,
+    ) -> Result<(), Error> {
+        access_control::AccessControl::require_auth_manager(&env, &caller)?;
+
+        // Get the authority
+        let mut authority_details =
+            storage::get_authority(&env, &authority).ok_or(Error::AuthorityNotFound)?;
+
+        if name.is_empty() {
+            return Err(Error::InvalidParameter);
+        }
+
+        // Update the authority details
+        authority_details.name = name;
+        authority_details.verification_key = verification_key;
+        authority_details.is_active = is_active;
+        storage::set_authority(&env, &authority_details);
+
+        // Emit authority updated event
+        events::emit_authority_updated(&env, &authority, &caller);
+
+        Ok(())
+    }
+
+    /// Deactivate a certification authority so it can no longer issue certificates
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address deactivating the authority (AUTH_MANAGER or ADMIN)
+    /// * `authority` - The address of the authority
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn deactivate_authority(
+        env: Env,
+        caller: Address,
+        authority: Address,
+    ) -> Result<(), Error> {
+        access_control::AccessControl::require_auth_manager(&env, &caller)?;
+
+        // Get the authority
+        let mut authority_details =
+            storage::get_authority(&env, &authority).ok_or(Error::AuthorityNotFound)?;
+
+        if !authority_details.is_active {
+            return Err(Error::AuthorityInactive);
+        }
+
+        // Mark the authority as inactive
+        authority_details.is_active = false;
+        storage::set_authority(&env, &authority_details);
+
+        // Emit authority updated event
+        events::emit_authority_updated(&env, &authority, &caller);
+
+        Ok(())
+    }
+
+    /// Get the details of a certification authority
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `authority` - The address of the authority
+    ///
+    /// # Returns
+    /// * `Result<CertificationAuthority, Error>` - The authority details or an error
+    pub fn get_authority(env: Env, authority: Address) -> Result<CertificationAuthority, Error> {
+        storage::get_authority(&env, &authority).ok_or(Error::AuthorityNotFound)
+    }
+
+    /// List all certification authorities
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    ///
+    /// # Returns
+    /// * `Vec<Address>` - A list of authority addresses
+    pub fn list_authorities(env: Env) -> Vec<Address> {
+        storage::get_all_authority_addresses(&env)
+    }
+
+    /// Grant a role to an address
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address granting the role (ADMIN)
+    /// * `address` - The address to grant the role to
+    /// * `role` - The role to grant
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn grant_role(
+        env: Env,
+        caller: Address,
+        address: Address,
+        role: Symbol,
+    ) -> Result<(), Error> {
+        access_control::AccessControl::grant_role(&env, &caller, &address, &role)
+    }
+
+    /// Revoke a role from an address
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address revoking the role (ADMIN)
+    /// * `address` - The address to revoke the role from
+    /// * `role` - The role to revoke
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn revoke_role(
+        env: Env,
+        caller: Address,
+        address: Address,
+        role: Symbol,
+    ) -> Result<(), Error> {
+        access_control::AccessControl::revoke_role(&env, &caller, &address, &role)
+    }
+
+    /// Renounce a role held by the caller
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `caller` - The address giving up the role
+    /// * `role` - The role to renounce
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Success or an error
+    pub fn renounce_role(env: Env, caller: Address, role: Symbol) -> Result<(), Error> {
+        access_control::AccessControl::renounce_role(&env, &caller, &role)
+    }
+
+    /// Check if an address has a specific role
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `address` - The address to check
+    /// * `role` - The role to check for
+    ///
+    /// # Returns
+    /// * `bool` - True if the address has the role
+    pub fn has_role(env: Env, address: Address, role: Symbol) -> bool {
+        access_control::AccessControl::has_role(&env, &address, &role)
+    }
+
+    /// Get all roles for an address
+    ///
+    /// # Arguments
+    /// * `env` - The environment object
+    /// * `address` - The address to get roles for
+    ///
+    /// # Returns
+    /// * `Vec<Symbol>` - A list of roles for the address
+    pub fn get_roles(env: Env, address: Address) -> Vec<Symbol> {
+        access_control::AccessControl::get_roles(&env, &address)
+    }
+}
+
+// Internal helpers shared by the contract entrypoints
+impl SuperCertificationContract {
+    /// Issue a certificate on behalf of an already authorized issuer, unless its
+    /// type needs approval from several issuers
+    fn issue(
+        env: &Env,
+        issuer: &Address,
+        cert_id: &String,
+        owner: &Address,
+        metadata: CertificateMetadata,
+        signature: &BytesN<64>,
+        template: Option<&CertificateTemplate>,
+    ) -> Result<(), Error> {
+        if Self::approval_threshold(env, &metadata.cert_type) > 1 {
+            return Err(Error::ApprovalRequired);
+        }
+
+        // An open draft holds on to its ID
+        if let Some(draft) = storage::get_draft(env, cert_id) {
+            if env.ledger().timestamp() <= draft.expires_at {
+                return Err(Error::CertificateAlreadyExists);
+            }
+        }
+
+        Self::create(
+            env, issuer, cert_id, owner, metadata, signature, template, false,
+        )
+    }
+
+    /// Suspend a certificate on behalf of an already authorized issuer
+    fn suspend(
+        env: &Env,
+        caller: &Address,
+        cert_id: &String,
+        until: u64,
+        reason: String,
+    ) -> Result<(), Error> {
+        // The suspension must end in the future, if it ends at all
+        let now = env.ledger().timestamp();
+        if until != 0 && until <= now {
+            return Err(Error::InvalidParameter);
+        }
+
+        // Only active certificates can be suspended
+        let mut certificate = storage::get_certificate(env, cert_id);
+        let old_status = utils::Utils::effective_status(env, &certificate);
+        match old_status {
+            CertificateStatus::Active => {}
+            CertificateStatus::Revoked => return Err(Error::CertificateRevoked),
+            CertificateStatus::Expired => return Err(Error::CertificateExpired),
+            CertificateStatus::Suspended => return Err(Error::CertificateSuspended),
+            CertificateStatus::Renounced => return Err(Error::CertificateRenounced),
+        }
+
+        // Record the suspension in the certificate's history
+        let mut history = storage::get_suspension_history(env, cert_id);
+        history.push_back(SuspensionRecord {
+            reason,
+            suspended_by: caller.clone(),
+            suspended_at: now,
+            until,
+            reinstated_at: 0,
+            reinstated_by: None,
+        });
+        storage::set_suspension_history(env, cert_id, &history);
+
+        // Suspend the certificate
+        certificate.status = CertificateStatus::Suspended;
+        certificate.suspended_until = until;
+        certificate.last_updated = now;
+        storage::set_certificate(env, cert_id, &certificate);
+
+        events::emit_status_changed(
+            env,
+            cert_id,
+            &old_status,
+            &CertificateStatus::Suspended,
+            caller,
+        );
+
+        Ok(())
+    }
+
+    /// Lift the suspension of a certificate on behalf of an already authorized issuer
+    fn reinstate(env: &Env, caller: &Address, cert_id: &String) -> Result<(), Error> {
+        // Only certificates that are still suspended can be reinstated
+        let mut certificate = storage::get_certificate(env, cert_id);
+        if utils::Utils::effective_status(env, &certificate) != CertificateStatus::Suspended {
+            return Err(Error::InvalidCertificateStatus);
+        }
+
+        // Close the open suspension in the history
+        let now = env.ledger().timestamp();
+        let mut history = storage::get_suspension_history(env, cert_id);
+        if let Some(mut record) = history.last() {
+            record.reinstated_at = now;
+            record.reinstated_by = Some(caller.clone());
+            history.set(history.len() - 1, record);
+            storage::set_suspension_history(env, cert_id, &history);
+        }
+
+        // Reinstate the certificate
+        certificate.status = CertificateStatus::Active;
+        certificate.suspended_until = 0;
+        certificate.last_updated = now;
+        storage::set_certificate(env, cert_id, &certificate);
+
+        // The certificate may have expired while it was suspended
+        let new_status = utils::Utils::effective_status(env, &certificate);
+        events::emit_status_changed(
+            env,
+            cert_id,
+            &CertificateStatus::Suspended,
+            &new_status,
+            caller,
+        );
+
+        Ok(())
+    }
+
+    /// Build a template version, checking its title and description pattern
+    fn new_template(
+        env: &Env,
+        caller: &Address,
+        template_id: String,
+        version: u32,
+        cert_type: CertificateType,
+        title: String,
+        description_pattern: String,
+        default_validity_secs: u64,
+        required_custom_fields: Vec<String>,
+    ) -> Result<CertificateTemplate, Error> {
+        utils::Utils::validate_metadata(&title)?;
+        if description_pattern.len() as usize > utils::MAX_DESCRIPTION_LENGTH {
+            return Err(Error::InvalidMetadata);
+        }
+
+        Ok(CertificateTemplate {
+            template_id,
+            version,
+            cert_type,
+            title,
+            description_pattern,
+            default_validity_secs,
+            required_custom_fields,
+            is_active: true,
+            updated_by: caller.clone(),
+            updated_at: env.ledger().timestamp(),
+        })
+    }
+
+    /// Build the metadata of a new certificate issued now
+    fn new_metadata(
+        env: &Env,
+        title: &String,
+        description: String,
+        cert_type: CertificateType,
+        expiration_date: u64,
+        custom_fields: Map<String, String>,
+    ) -> CertificateMetadata {
+        CertificateMetadata {
+            title: title.clone(),
+            description,
+            issue_date: env.ledger().timestamp(),
+            expiration_date,
+            cert_type,
+            custom_fields,
+        }
+    }
+
+    /// Store a new certificate and index it under its owner, charging the
+    /// issuer the issuance fee directly or, for drafts, through its allowance
+    fn create(
+        env: &Env,
+        issuer: &Address,
+        cert_id: &String,
+        owner: &Address,
+        metadata: CertificateMetadata,
+        signature: &BytesN<64>,
+        template: Option<&CertificateTemplate>,
+        from_draft: bool,
+    ) -> Result<(), Error> {
+        // Check the issuer is a registered and active certification authority
+        let issuing_authority =
+            storage::get_authority(env, issuer).ok_or(Error::AuthorityNotFound)?;
+        if !issuing_authority.is_active {
+            return Err(Error::AuthorityInactive);
+        }
+
+        // Check if certificate already exists
+        if storage::has_certificate(env, cert_id) {
+            return Err(Error::CertificateAlreadyExists);
+        }
+
+        // Check the custom fields against the configured limits and the type's schema
+        utils::Utils::validate_custom_fields(
+            &storage::Storage::get_config(env),
+            &metadata.custom_fields,
+        )?;
+        let schema_id = storage::Storage::get_type_schemas(env).get(metadata.cert_type.clone());
+        Self::check_schema(env, cert_id, &schema_id, &metadata.custom_fields)?;
+
+        // Check the signature with the issuer's current key; a bad signature traps
+        utils::Utils::verify_signature(
+            env,
+            &utils::signing_digest(env, cert_id, &metadata, template.is_some()),
+            signature,
+            &issuing_authority.verification_key,
+        );
+
+        // Check the issuer's quota, then collect the fee; nothing after this can fail
+        let cert_type = metadata.cert_type.clone();
+        let usage = Self::check_quota(env, issuer, &cert_type, 1)?;
+        if from_draft {
+            Self::charge_draft_fee(env, issuer, &cert_type)?;
+        } else {
+            Self::charge_fee(env, issuer, &cert_type, 1)?;
+        }
+        storage::set_issuer_usage(env, issuer, &usage);
+
+        // Create certificate details
+        let cert_details = CertificateDetails {
+            owner: owner.clone(),
+            issuer: issuer.clone(),
+            metadata_hash: utils::bytes_to_string(
+                env,
+                &utils::hash_metadata(env, cert_id, &metadata),
+            ),
+            metadata,
+            status: CertificateStatus::Active,
+            signature: signature.clone(),
+            version: 1,
+            revocation_reason: None,
+            suspended_until: 0,
+            transferable: false,
+            template_id: template.map(|template| template.template_id.clone()),
+            template_version: template.map_or(0, |template| template.version),
+            schema_id,
+            fields_root: None,
+            signing_key: Some(issuing_authority.verification_key),
+            last_updated: env.ledger().timestamp(),
+        };
+
+        // Store the certificate and index it under its owner
+        storage::set_certificate(env, cert_id, &cert_details);
+        storage::add_owner_certificate(env, owner, cert_id);
+        Self::record_version(env, cert_id, &cert_details, issuer);
+
+        // Emit certificate issued event
+        events::emit_certificate_issued(env, cert_id, owner, issuer, &cert_type);
+
+        Ok(())
+    }
+
+    /// Count new certificates against an issuer's quota, failing if a limit would
+    /// be exceeded, and return the usage to store once the certificates are issued
+    fn check_quota(
+        env: &Env,
+        issuer: &Address,
+        cert_type: &CertificateType,
+        count: u32,
+    ) -> Result<IssuerUsage, Error> {
+        let now = env.ledger().timestamp();
+        let mut usage = storage::get_issuer_usage(env, issuer);
+        let type_count = usage.by_type.get(cert_type.clone()).unwrap_or(0);
+
+        if let Some(quota) = storage::get_issuer_quota(env, issuer) {
+            // Periods are fixed windows starting at the first issuance after the previous one ended
+            if quota.period_length != 0
+                && now >= usage.period_start.saturating_add(quota.period_length)
+            {
+                usage.period_start = now;
+                usage.period_count = 0;
+            }
+
+            let type_limit = quota.type_limits.get(cert_type.clone()).unwrap_or(0);
+            let exceeds = |used: u32, limit: u32| limit != 0 && used.saturating_add(count) > limit;
+            if exceeds(usage.total, quota.total_limit)
+                || exceeds(usage.period_count, quota.period_limit)
+                || exceeds(type_count, type_limit)
+            {
+                return Err(Error::QuotaExceeded);
+            }
+        }
+
+        usage.total = usage.total.saturating_add(count);
+        usage.period_count = usage.period_count.saturating_add(count);
+        usage
+            .by_type
+            .set(cert_type.clone(), type_count.saturating_add(count));
+
+        Ok(usage)
+    }
+
+    /// Get the fee token, treasury and amount an issuer owes for `count`
+    /// certificates of a type, or `None` if it owes nothing
+    fn issuance_fee(
+        env: &Env,
+        issuer: &Address,
+        cert_type: &CertificateType,
+        count: u32,
+    ) -> Result<Option<(Address, Address, i128)>, Error> {
+        let config = storage::Storage::get_config(env);
+        let (Some(fee_token), Some(treasury)) = (config.fee_token, config.fee_treasury) else {
+            return Ok(None);
+        };
+
+        let amount = config.issuance_fees.get(cert_type.clone()).unwrap_or(0);
+        if amount == 0 || storage::is_fee_exempt(env, issuer) {
+            return Ok(None);
+        }
+        let amount = amount
+            .checked_mul(i128::from(count))
+            .ok_or(Error::FeePaymentFailed)?;
+
+        Ok(Some((fee_token, treasury, amount)))
+    }
+
+    /// Transfer the issuance fee of `count` certificates of a type from the issuer to the treasury
+    fn charge_fee(
+        env: &Env,
+        issuer: &Address,
+        cert_type: &CertificateType,
+        count: u32,
+    ) -> Result<(), Error> {
+        let Some((fee_token, treasury, amount)) =
+            Self::issuance_fee(env, issuer, cert_type, count)?
+        else {
+            return Ok(());
+        };
+
+        // Report a failed transfer, such as an insufficient balance, instead of trapping
+        match token::TokenClient::new(env, &fee_token).try_transfer(issuer, &treasury, &amount) {
+            Ok(Ok(())) => Ok(()),
+            _ => Err(Error::FeePaymentFailed),
+        }
+    }
+
+    /// Raise the contract's allowance over the proposer's fee tokens by the
+    /// issuance fee of a draft, to be collected when the draft is issued
+    fn approve_draft_fee(
+        env: &Env,
+        proposer: &Address,
+        cert_type: &CertificateType,
+    ) -> Result<(), Error> {
+        let Some((fee_token, _, amount)) = Self::issuance_fee(env, proposer, cert_type, 1)? else {
+            return Ok(());
+        };
+
+        let token = token::TokenClient::new(env, &fee_token);
+        let contract = env.current_contract_address();
+        let amount = token
+            .allowance(proposer, &contract)
+            .checked_add(amount)
+            .ok_or(Error::FeePaymentFailed)?;
+
+        // The allowance lapses a day or two after the draft would have expired
+        let window_days = storage::Storage::get_config(env).approval_window / 86_400;
+        let live_until = env
+            .ledger()
+            .sequence()
+            .saturating_add(
+                u32::try_from(window_days.saturating_add(2))
+                    .unwrap_or(u32::MAX)
+                    .saturating_mul(types::DAY_IN_LEDGERS),
+            )
+            .min(env.ledger().max_live_until_ledger() - 1);
+        match token.try_approve(proposer, &contract, &amount, &live_until) {
+            Ok(Ok(())) => Ok(()),
+            _ => Err(Error::FeePaymentFailed),
+        }
+    }
+
+    /// Collect the issuance fee of a draft through the allowance its proposer granted
+    fn charge_draft_fee(
+        env: &Env,
+        proposer: &Address,
+        cert_type: &CertificateType,
+    ) -> Result<(), Error> {
+        let Some((fee_token, treasury, amount)) = Self::issuance_fee(env, proposer, cert_type, 1)?
+        else {
+            return Ok(());
+        };
+
+        let contract = env.current_contract_address();
+        match token::TokenClient::new(env, &fee_token)
+            .try_transfer_from(&contract, proposer, &treasury, &amount)
+        {
+            Ok(Ok(())) => Ok(()),
+            _ => Err(Error::FeePaymentFailed),
+        }
+    }
+
+    /// Check custom fields against a credential schema, if any, reporting the
+    /// offending field in an event when they do not conform
+    fn check_schema(
+        env: &Env,
+        cert_id: &String,
+        schema_id: &Option<String>,
+        custom_fields: &Map<String, String>,
+    ) -> Result<(), Error> {
+        let Some(schema_id) = schema_id else {
+            return Ok(());
+        };
+        let schema = storage::get_schema(env, schema_id).ok_or(Error::SchemaNotFound)?;
+
+        match utils::Utils::schema_violation(&schema, custom_fields) {
+            Some(field) => {
+                events::emit_schema_violation(env, cert_id, schema_id, &field);
+                Err(Error::InvalidMetadata)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Get the number of approvals needed for a certificate type
+    fn approval_threshold(env: &Env, cert_type: &CertificateType) -> u32 {
+        storage::Storage::get_approval_thresholds(env)
+            .get(cert_type.clone())
+            .unwrap_or(1)
+    }
+
+    /// Issue the certificate of a draft that has enough approvals, or store the draft
+    fn store_or_issue_draft(
+        env: &Env,
+        cert_id: &String,
+        draft: CertificateDraft,
+    ) -> Result<(), Error> {
+        if draft.approvals.len() < draft.threshold {
+            storage::set_draft(env, cert_id, &draft);
+            return Ok(());
+        }
+
+        storage::remove_draft(env, cert_id);
+        let metadata = Self::new_metadata(
+            env,
+            cert_id,
+            draft.description,
+            draft.cert_type,
+            draft.expiration_date,
+            draft.custom_fields,
+        );
+        Self::create(
+            env,
+            &draft.proposer,
+            cert_id,
+            &draft.owner,
+            metadata,
+            &draft.signature,
+            None,
+            true,
+        )
+    }
+
+    /// Check the caller may amend a certificate and load it
+    fn load_for_update(
+        env: &Env,
+        caller: &Address,
+        cert_id: &String,
+    ) -> Result<CertificateDetails, Error> {
+        // Check if the caller is authorized to issue certificates
+        access_control::AccessControl::require_issuer(env, caller)?;
+
+        // Check if certificate exists
+        if !storage::has_certificate(env, cert_id) {
+            return Err(Error::CertificateNotFound);
+        }
+
+        // Expired and renounced certificates can no longer be amended
+        let certificate = storage::get_certificate(env, cert_id);
+        match utils::Utils::effective_status(env, &certificate) {
+            CertificateStatus::Expired => return Err(Error::CertificateExpired),
+            CertificateStatus::Renounced => return Err(Error::CertificateRenounced),
+            _ => {}
+        }
+
+        // Private certificates commit to their fields in the description, which must not change
+        if certificate.fields_root.is_some() {
+            return Err(Error::PrivateCertificate);
+        }
+
+        Ok(certificate)
+    }
+
+    /// Check the signature of amended metadata, rehash it, bump the version,
+    /// store it and announce the update
+    fn commit_update(
+        env: &Env,
+        caller: &Address,
+        cert_id: &String,
+        mut certificate: CertificateDetails,
+        old_hash: String,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        // Amendments are signed with the issuing authority's current key
+        let authority =
+            storage::get_authority(env, &certificate.issuer).ok_or(Error::AuthorityNotFound)?;
+        if !authority.is_active {
+            return Err(Error::AuthorityInactive);
+        }
+        utils::Utils::verify_signature(
+            env,
+            &utils::signing_digest(
+                env,
+                cert_id,
+                &certificate.metadata,
+                certificate.template_id.is_some(),
+            ),
+            &signature,
+            &authority.verification_key,
+        );
+        certificate.signing_key = Some(authority.verification_key);
+
+        certificate.metadata_hash = utils::bytes_to_string(
+            env,
+            &utils::hash_metadata(env, cert_id, &certificate.metadata),
+        );
         certificate.signature = signature;
         certificate.version += 1;
         certificate.last_updated = env.ledger().timestamp();
 
-        // Update the certificate in storage
-        storage::set_certificate(&env, &cert_id, &certificate);
+        // Update the certificate in storage and keep the new version in its history
+        storage::set_certificate(env, cert_id, &certificate);
+        Self::record_version(env, cert_id, &certificate, caller);
+
+        events::emit_certificate_updated(
+            env,
+            cert_id,
+            &old_hash,
+            &certificate.metadata_hash,
+            certificate.version,
+            caller,
+        );
 
         Ok(())
     }
+
+    /// Record a new owner for a certificate and move it between the owners' indexes
+    fn move_to_owner(
+        env: &Env,
+        cert_id: &String,
+        certificate: &mut CertificateDetails,
+        new_owner: &Address,
+    ) {
+        storage::remove_owner_certificate(env, &certificate.owner, cert_id);
+        storage::add_owner_certificate(env, new_owner, cert_id);
+        storage::remove_pending_claim(env, cert_id);
+
+        certificate.owner = new_owner.clone();
+        certificate.last_updated = env.ledger().timestamp();
+        storage::set_certificate(env, cert_id, certificate);
+    }
+
+    /// Store a snapshot of the certificate's current version in its history
+    fn record_version(
+        env: &Env,
+        cert_id: &String,
+        certificate: &CertificateDetails,
+        updated_by: &Address,
+    ) {
+        storage::set_certificate_version(
+            env,
+            cert_id,
+            &CertificateVersion {
+                version: certificate.version,
+                metadata: certificate.metadata.clone(),
+                metadata_hash: certificate.metadata_hash.clone(),
+                signature: certificate.signature.clone(),
+                updated_at: certificate.last_updated,
+                updated_by: updated_by.clone(),
+            },
+        );
+    }
 }
 
 // Include test module
-//// #[cfg(test)]
-//// mod test;
-// This is commented because the test module is not working right now 😔
+mod test;
@@ -0,0 +1,59 @@
+use crate::errors::Error;
+use crate::storage::{self, Storage};
+use crate::utils::MAX_PAGE_SIZE;
+use crate::CONTRACT_VERSION;
+use soroban_sdk::{Env, String};
+
+/// Run one page of the pending data migrations and return the cursor of the next page
+///
+/// Certificates are migrated in issuance order, `limit` at a time, starting
+/// at the cursor returned by the previous page (0 for the first). Once the
+/// last page is done, the contract-wide data is migrated and the data version
+/// is advanced to `CONTRACT_VERSION`, so each migration runs exactly once.
+pub fn run_pending(env: &Env, cursor: u32, limit: u32) -> Result<Option<u32>, Error> {
+    let from = Storage::get_data_version(env);
+
+    if from >= CONTRACT_VERSION {
+        return Err(Error::MigrationNotRequired);
+    }
+
+    // Pages are migrated in order, each starting where the previous one ended
+    if limit == 0 || cursor != Storage::get_migration_cursor(env) {
+        return Err(Error::InvalidParameter);
+    }
+
+    let limit = limit.min(MAX_PAGE_SIZE);
+    for cert_id in storage::get_certificate_ids(env, cursor, limit) {
+        for version in (from + 1)..=CONTRACT_VERSION {
+            migrate_certificate(env, version, &cert_id);
+        }
+    }
+
+    let next = cursor.saturating_add(limit);
+    if next < Storage::get_certificate_count(env) {
+        Storage::set_migration_cursor(env, &next);
+        return Ok(Some(next));
+    }
+
+    for version in (from + 1)..=CONTRACT_VERSION {
+        migrate_contract(env, version);
+    }
+
+    Storage::set_migration_cursor(env, &0);
+    Storage::set_data_version(env, &CONTRACT_VERSION);
+    Ok(None)
+}
+
+/// Rewrite a certificate and its related entries into the layout of `version`
+///
+/// Version 1 is the first version that can be upgraded, so no step exists yet.
+/// A later version that changes the certificate layout adds a branch here; it
+/// must leave certificates already in the new layout as they are, as those
+/// issued between the upgrade and the end of the migration are.
+fn migrate_certificate(_env: &Env, _version: u32, _cert_id: &String) {}
+
+/// Rewrite the contract-wide entries, such as the configuration, into the
+/// layout of `version`
+///
+/// Like `migrate_certificate`, this has no step yet.
+fn migrate_contract(_env: &Env, _version: u32) {}
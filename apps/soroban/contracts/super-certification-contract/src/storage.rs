@@ -1,5 +1,9 @@
-use crate::types::{CertificateDetails, CertificationAuthority};
-use soroban_sdk::{symbol_short, Address, Env, Map, String, Symbol, Vec};
+use crate::types::{
+    CertificateBatch, CertificateDetails, CertificateDraft, CertificateTemplate, CertificateType,
+    CertificateVersion, CertificationAuthority, ContractConfig, CredentialSchema, IssuerQuota,
+    IssuerUsage, PendingClaim, RevocationRecord, SuspensionRecord,
+};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, String, Symbol, Vec};
 
 /// Storage keys for the contract
 pub struct StorageKeys;
@@ -7,27 +11,70 @@ pub struct StorageKeys;
 impl StorageKeys {
     /// Admin role storage key
     pub const ADMIN: Symbol = symbol_short!("ADMIN");
-    /// Certificates map storage key
-    pub const CERTIFICATES: Symbol = symbol_short!("CERTS");
+    /// Certificate counter storage key
+    pub const CERTIFICATE_COUNT: Symbol = symbol_short!("CERT_CNT");
     /// Certification authorities map storage key
     pub const AUTHORITIES: Symbol = symbol_short!("AUTH");
     /// Roles map storage key
     pub const ROLES: Symbol = symbol_short!("ROLES");
     /// Contract configuration storage key
     pub const CONFIG: Symbol = symbol_short!("CONFIG");
-    /// Contract version storage key
-    pub const VERSION: Symbol = symbol_short!("VER");
+    /// Version of the stored data layout, advanced by migrations
+    pub const DATA_VERSION: Symbol = symbol_short!("DATA_VER");
+    /// Position in the issuance index the next migration page starts at
+    pub const MIGRATION_CURSOR: Symbol = symbol_short!("MIG_CUR");
+    /// Approval thresholds by certificate type storage key
+    pub const APPROVAL_THRESHOLDS: Symbol = symbol_short!("APPR_TH");
+    /// Credential schema by certificate type storage key
+    pub const TYPE_SCHEMAS: Symbol = symbol_short!("TYPE_SCH");
+}
+
+/// Keys for entries kept in persistent storage, one ledger entry per key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    /// Certificate details by certificate ID
+    Certificate(String),
+    /// Certificate ID by issuance position
+    CertificateIndex(u32),
+    /// Number of certificates held by an owner
+    OwnerCertificateCount(Address),
+    /// Certificate ID by owner and position in the owner's index
+    OwnerCertificate(Address, u32),
+    /// Position of a certificate in its owner's index
+    OwnerCertificatePosition(String),
+    /// Suspension history of a certificate
+    SuspensionHistory(String),
+    /// Revocation details of a certificate
+    Revocation(String),
+    /// Snapshot of a certificate at a given version
+    CertificateVersion(String, u32),
+    /// Pending claim of a certificate issued to a claim key
+    PendingClaim(String),
+    /// Certificate draft waiting for approvals
+    Draft(String),
+    /// Issuance quota of an issuer
+    IssuerQuota(Address),
+    /// Issuance counts of an issuer
+    IssuerUsage(Address),
+    /// Marks an issuer as exempt from issuance fees
+    FeeExempt(Address),
+    /// Latest version of a certificate template
+    Template(String),
+    /// Certificate template at a given version
+    TemplateVersion(String, u32),
+    /// Credential schema by schema ID
+    Schema(String),
+    /// Certificate batch by batch ID
+    Batch(String),
+    /// Revocation details of a batch member by batch ID and leaf hash
+    BatchRevocation(String, BytesN<32>),
 }
 
 /// Storage helper functions
 pub struct Storage;
 
 impl Storage {
-    /// Get the admin address
-    pub fn get_admin(env: &Env) -> Address {
-        env.storage().instance().get(&StorageKeys::ADMIN).unwrap()
-    }
-
     /// Set the admin address
     pub fn set_admin(env: &Env, admin: &Address) {
         env.storage().instance().set(&StorageKeys::ADMIN, admin);
@@ -38,29 +85,19 @@ impl Storage {
         env.storage().instance().has(&StorageKeys::ADMIN)
     }
 
-    /// Get the certificates map
-    pub fn get_certificates(env: &Env) -> Map<String, CertificateDetails> {
+    /// Get the number of certificates issued
+    pub fn get_certificate_count(env: &Env) -> u32 {
         env.storage()
             .instance()
-            .get(&StorageKeys::CERTIFICATES)
-            .unwrap()
+            .get(&StorageKeys::CERTIFICATE_COUNT)
+            .unwrap_or(0)
     }
 
-    /// Set the certificates map
-    pub fn set_certificates(env: &Env, certificates: &Map<String, CertificateDetails>) {
+    /// Set the number of certificates issued
+    pub fn set_certificate_count(env: &Env, count: u32) {
         env.storage()
             .instance()
-            .set(&StorageKeys::CERTIFICATES, certificates);
-    }
-
-    /// Initialize certificates map if not exists
-    pub fn init_certificates(env: &Env) {
-        if !env.storage().instance().has(&StorageKeys::CERTIFICATES) {
-            let certificates: Map<String, CertificateDetails> = Map::new(env);
-            env.storage()
-                .instance()
-                .set(&StorageKeys::CERTIFICATES, &certificates);
-        }
+            .set(&StorageKeys::CERTIFICATE_COUNT, &count);
     }
 
     /// Get the authorities map
@@ -106,65 +143,602 @@ impl Storage {
         }
     }
 
+    /// Get the version of the stored data layout
+    pub fn get_data_version(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&StorageKeys::DATA_VERSION)
+            .unwrap_or(1)
+    }
+
+    /// Set the version of the stored data layout
+    pub fn set_data_version(env: &Env, version: &u32) {
+        env.storage()
+            .instance()
+            .set(&StorageKeys::DATA_VERSION, version);
+    }
+
+    /// Get the position the next migration page starts at
+    pub fn get_migration_cursor(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&StorageKeys::MIGRATION_CURSOR)
+            .unwrap_or(0)
+    }
+
+    /// Set the position the next migration page starts at
+    pub fn set_migration_cursor(env: &Env, cursor: &u32) {
+        env.storage()
+            .instance()
+            .set(&StorageKeys::MIGRATION_CURSOR, cursor);
+    }
+
+    /// Get the contract configuration, falling back to the defaults
+    pub fn get_config(env: &Env) -> ContractConfig {
+        env.storage()
+            .instance()
+            .get(&StorageKeys::CONFIG)
+            .unwrap_or_else(|| ContractConfig::new(env))
+    }
+
+    /// Set the contract configuration
+    pub fn set_config(env: &Env, config: &ContractConfig) {
+        env.storage().instance().set(&StorageKeys::CONFIG, config);
+    }
+
+    /// Get the approval thresholds by certificate type
+    pub fn get_approval_thresholds(env: &Env) -> Map<CertificateType, u32> {
+        env.storage()
+            .instance()
+            .get(&StorageKeys::APPROVAL_THRESHOLDS)
+            .unwrap_or(Map::new(env))
+    }
+
+    /// Set the approval thresholds by certificate type
+    pub fn set_approval_thresholds(env: &Env, thresholds: &Map<CertificateType, u32>) {
+        env.storage()
+            .instance()
+            .set(&StorageKeys::APPROVAL_THRESHOLDS, thresholds);
+    }
+
+    /// Get the credential schema bound to each certificate type
+    pub fn get_type_schemas(env: &Env) -> Map<CertificateType, String> {
+        env.storage()
+            .instance()
+            .get(&StorageKeys::TYPE_SCHEMAS)
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    /// Set the credential schema bound to each certificate type
+    pub fn set_type_schemas(env: &Env, schemas: &Map<CertificateType, String>) {
+        env.storage()
+            .instance()
+            .set(&StorageKeys::TYPE_SCHEMAS, schemas);
+    }
+
+    /// Extend the TTL of the contract instance once it drops below the configured threshold
+    pub fn extend_instance_ttl(env: &Env) {
+        let config = Self::get_config(env);
+        env.storage()
+            .instance()
+            .extend_ttl(config.ttl_threshold, config.ttl_extend_to);
+    }
+
     /// Initialize all storage
     pub fn init_all(env: &Env) {
-        Self::init_certificates(env);
         Self::init_authorities(env);
         Self::init_roles(env);
 
-        // Set contract version
-        if !env.storage().instance().has(&StorageKeys::VERSION) {
-            env.storage().instance().set(&StorageKeys::VERSION, &1u32);
+        // New data is written in the layout of the running code
+        if !env.storage().instance().has(&StorageKeys::DATA_VERSION) {
+            Self::set_data_version(env, &crate::CONTRACT_VERSION);
         }
+
+        // Set the default configuration
+        if !env.storage().instance().has(&StorageKeys::CONFIG) {
+            Self::set_config(env, &ContractConfig::new(env));
+        }
+        Self::extend_instance_ttl(env);
     }
 }
 
 // Standalone storage functions for direct use in the contract
 
-/// Check if a certificate exists
-pub fn has_certificate(env: &Env, cert_id: &String) -> bool {
-    if !env.storage().instance().has(&StorageKeys::CERTIFICATES) {
-        return false;
+/// Extend the TTL of a persistent entry once it drops below the configured threshold
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    let config = Storage::get_config(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, config.ttl_threshold, config.ttl_extend_to);
+}
+
+/// Extend the TTL of a certificate to the configured maximum, regardless of the threshold
+///
+/// Besides the certificate entry, this covers the entries that make up its
+/// record: the revocation details, suspension history, version snapshots and
+/// its slot in the owner's index.
+pub fn extend_certificate_ttl(env: &Env, cert_id: &String) {
+    let config = Storage::get_config(env);
+    let persistent = env.storage().persistent();
+    let extend = |key: &DataKey| {
+        if persistent.has(key) {
+            persistent.extend_ttl(key, config.ttl_extend_to, config.ttl_extend_to);
+        }
+    };
+
+    let key = DataKey::Certificate(cert_id.clone());
+    let certificate: CertificateDetails = persistent.get(&key).unwrap();
+    extend(&key);
+    extend(&DataKey::Revocation(cert_id.clone()));
+    extend(&DataKey::SuspensionHistory(cert_id.clone()));
+    for version in 1..=certificate.version {
+        extend(&DataKey::CertificateVersion(cert_id.clone(), version));
     }
 
-    let certificates = Storage::get_certificates(env);
-    certificates.contains_key(cert_id.clone())
+    let position_key = DataKey::OwnerCertificatePosition(cert_id.clone());
+    if let Some(position) = persistent.get::<_, u32>(&position_key) {
+        extend(&position_key);
+        extend(&DataKey::OwnerCertificate(certificate.owner, position));
+    }
+
+    Storage::extend_instance_ttl(env);
+}
+
+/// Check if a certificate exists
+pub fn has_certificate(env: &Env, cert_id: &String) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Certificate(cert_id.clone()))
 }
 
 /// Get a certificate by ID
 pub fn get_certificate(env: &Env, cert_id: &String) -> CertificateDetails {
-    let certificates = Storage::get_certificates(env);
-    certificates.get(cert_id.clone()).unwrap()
+    let key = DataKey::Certificate(cert_id.clone());
+    let certificate = env.storage().persistent().get(&key).unwrap();
+
+    // Keep certificates that are still being read in live state
+    extend_persistent_ttl(env, &key);
+    Storage::extend_instance_ttl(env);
+
+    certificate
 }
 
-/// Set a certificate
+/// Set a certificate, recording it in the issuance index the first time it is stored
 pub fn set_certificate(env: &Env, cert_id: &String, certificate: &CertificateDetails) {
-    let mut certificates = Storage::get_certificates(env);
-    certificates.set(cert_id.clone(), certificate.clone());
-    Storage::set_certificates(env, &certificates);
+    if !has_certificate(env, cert_id) {
+        let count = Storage::get_certificate_count(env);
+        let index_key = DataKey::CertificateIndex(count);
+        env.storage().persistent().set(&index_key, cert_id);
+        extend_persistent_ttl(env, &index_key);
+        Storage::set_certificate_count(env, count + 1);
+    }
+
+    let key = DataKey::Certificate(cert_id.clone());
+    env.storage().persistent().set(&key, certificate);
+    extend_persistent_ttl(env, &key);
+    Storage::extend_instance_ttl(env);
 }
 
-/// Get all certificate IDs
-pub fn get_all_certificate_ids(env: &Env) -> Vec<String> {
-    if !env.storage().instance().has(&StorageKeys::CERTIFICATES) {
-        return Vec::new(env);
+/// Get a range of certificate IDs in issuance order, starting at `start`
+pub fn get_certificate_ids(env: &Env, start: u32, limit: u32) -> Vec<String> {
+    let count = Storage::get_certificate_count(env);
+    let end = count.min(start.saturating_add(limit));
+    let mut cert_ids = Vec::new(env);
+
+    for index in start..end {
+        let key = DataKey::CertificateIndex(index);
+        let cert_id: String = env.storage().persistent().get(&key).unwrap();
+        extend_persistent_ttl(env, &key);
+        cert_ids.push_back(cert_id);
+    }
+
+    cert_ids
+}
+
+/// Get the number of certificates held by an owner
+pub fn get_owner_certificate_count(env: &Env, owner: &Address) -> u32 {
+    let key = DataKey::OwnerCertificateCount(owner.clone());
+    match env.storage().persistent().get(&key) {
+        Some(count) => {
+            extend_persistent_ttl(env, &key);
+            count
+        }
+        None => 0,
+    }
+}
+
+/// Add a certificate to its owner's index
+pub fn add_owner_certificate(env: &Env, owner: &Address, cert_id: &String) {
+    let count = get_owner_certificate_count(env, owner);
+    let persistent = env.storage().persistent();
+
+    let entry_key = DataKey::OwnerCertificate(owner.clone(), count);
+    let position_key = DataKey::OwnerCertificatePosition(cert_id.clone());
+    let count_key = DataKey::OwnerCertificateCount(owner.clone());
+
+    persistent.set(&entry_key, cert_id);
+    persistent.set(&position_key, &count);
+    persistent.set(&count_key, &(count + 1));
+
+    extend_persistent_ttl(env, &entry_key);
+    extend_persistent_ttl(env, &position_key);
+    extend_persistent_ttl(env, &count_key);
+}
+
+/// Remove a certificate from its owner's index, moving the owner's last certificate into its slot
+pub fn remove_owner_certificate(env: &Env, owner: &Address, cert_id: &String) {
+    let persistent = env.storage().persistent();
+    let position_key = DataKey::OwnerCertificatePosition(cert_id.clone());
+    let position: u32 = match persistent.get(&position_key) {
+        Some(position) => position,
+        None => return,
+    };
+
+    let last = get_owner_certificate_count(env, owner) - 1;
+    let last_key = DataKey::OwnerCertificate(owner.clone(), last);
+    if position != last {
+        let moved: String = persistent.get(&last_key).unwrap();
+        let entry_key = DataKey::OwnerCertificate(owner.clone(), position);
+        let moved_position_key = DataKey::OwnerCertificatePosition(moved.clone());
+
+        persistent.set(&entry_key, &moved);
+        persistent.set(&moved_position_key, &position);
+
+        extend_persistent_ttl(env, &entry_key);
+        extend_persistent_ttl(env, &moved_position_key);
     }
 
-    let certificates = Storage::get_certificates(env);
+    let count_key = DataKey::OwnerCertificateCount(owner.clone());
+    persistent.remove(&last_key);
+    persistent.remove(&position_key);
+    persistent.set(&count_key, &last);
+    extend_persistent_ttl(env, &count_key);
+}
+
+/// Get a page of the certificate IDs held by an owner
+pub fn get_owner_certificate_ids(
+    env: &Env,
+    owner: &Address,
+    start: u32,
+    limit: u32,
+) -> Vec<String> {
+    let count = get_owner_certificate_count(env, owner);
+    let end = count.min(start.saturating_add(limit));
     let mut cert_ids = Vec::new(env);
 
-    for (id, _) in certificates.iter() {
-        cert_ids.push_back(id);
+    for position in start..end {
+        let key = DataKey::OwnerCertificate(owner.clone(), position);
+        let cert_id: String = env.storage().persistent().get(&key).unwrap();
+        extend_persistent_ttl(env, &key);
+        cert_ids.push_back(cert_id);
     }
 
     cert_ids
 }
 
+/// Get a version snapshot of a certificate
+pub fn get_certificate_version(
+    env: &Env,
+    cert_id: &String,
+    version: u32,
+) -> Option<CertificateVersion> {
+    let key = DataKey::CertificateVersion(cert_id.clone(), version);
+    let snapshot = env.storage().persistent().get(&key);
+    if snapshot.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    snapshot
+}
+
+/// Store a version snapshot of a certificate
+pub fn set_certificate_version(env: &Env, cert_id: &String, snapshot: &CertificateVersion) {
+    let key = DataKey::CertificateVersion(cert_id.clone(), snapshot.version);
+    env.storage().persistent().set(&key, snapshot);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Get the suspension history of a certificate, oldest first
+pub fn get_suspension_history(env: &Env, cert_id: &String) -> Vec<SuspensionRecord> {
+    let key = DataKey::SuspensionHistory(cert_id.clone());
+    match env.storage().persistent().get(&key) {
+        Some(history) => {
+            extend_persistent_ttl(env, &key);
+            history
+        }
+        None => Vec::new(env),
+    }
+}
+
+/// Set the suspension history of a certificate
+pub fn set_suspension_history(env: &Env, cert_id: &String, history: &Vec<SuspensionRecord>) {
+    let key = DataKey::SuspensionHistory(cert_id.clone());
+    env.storage().persistent().set(&key, history);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Get the revocation details of a certificate
+pub fn get_revocation(env: &Env, cert_id: &String) -> Option<RevocationRecord> {
+    let key = DataKey::Revocation(cert_id.clone());
+    let record = env.storage().persistent().get(&key);
+    if record.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    record
+}
+
+/// Set the revocation details of a certificate
+pub fn set_revocation(env: &Env, cert_id: &String, record: &RevocationRecord) {
+    let key = DataKey::Revocation(cert_id.clone());
+    env.storage().persistent().set(&key, record);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Get the pending claim of a certificate
+pub fn get_pending_claim(env: &Env, cert_id: &String) -> Option<PendingClaim> {
+    let key = DataKey::PendingClaim(cert_id.clone());
+    let claim = env.storage().persistent().get(&key);
+    if claim.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    claim
+}
+
+/// Set the pending claim of a certificate
+pub fn set_pending_claim(env: &Env, cert_id: &String, claim: &PendingClaim) {
+    let key = DataKey::PendingClaim(cert_id.clone());
+    env.storage().persistent().set(&key, claim);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Remove the pending claim of a certificate
+pub fn remove_pending_claim(env: &Env, cert_id: &String) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingClaim(cert_id.clone()));
+}
+
+/// Get a certificate draft
+pub fn get_draft(env: &Env, cert_id: &String) -> Option<CertificateDraft> {
+    let key = DataKey::Draft(cert_id.clone());
+    let draft = env.storage().persistent().get(&key);
+    if draft.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    draft
+}
+
+/// Set a certificate draft
+pub fn set_draft(env: &Env, cert_id: &String, draft: &CertificateDraft) {
+    let key = DataKey::Draft(cert_id.clone());
+    env.storage().persistent().set(&key, draft);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Remove a certificate draft
+pub fn remove_draft(env: &Env, cert_id: &String) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Draft(cert_id.clone()));
+}
+
+/// Get the issuance quota of an issuer
+pub fn get_issuer_quota(env: &Env, issuer: &Address) -> Option<IssuerQuota> {
+    let key = DataKey::IssuerQuota(issuer.clone());
+    let quota = env.storage().persistent().get(&key);
+    if quota.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    quota
+}
+
+/// Set the issuance quota of an issuer
+pub fn set_issuer_quota(env: &Env, issuer: &Address, quota: &IssuerQuota) {
+    let key = DataKey::IssuerQuota(issuer.clone());
+    env.storage().persistent().set(&key, quota);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Remove the issuance quota of an issuer
+pub fn remove_issuer_quota(env: &Env, issuer: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::IssuerQuota(issuer.clone()));
+}
+
+/// Get the issuance counts of an issuer
+pub fn get_issuer_usage(env: &Env, issuer: &Address) -> IssuerUsage {
+    let key = DataKey::IssuerUsage(issuer.clone());
+    match env.storage().persistent().get(&key) {
+        Some(usage) => {
+            extend_persistent_ttl(env, &key);
+            usage
+        }
+        None => IssuerUsage {
+            total: 0,
+            period_start: 0,
+            period_count: 0,
+            by_type: Map::new(env),
+        },
+    }
+}
+
+/// Set the issuance counts of an issuer
+pub fn set_issuer_usage(env: &Env, issuer: &Address, usage: &IssuerUsage) {
+    let key = DataKey::IssuerUsage(issuer.clone());
+    env.storage().persistent().set(&key, usage);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Check if an issuer is exempt from issuance fees
+pub fn is_fee_exempt(env: &Env, issuer: &Address) -> bool {
+    let key = DataKey::FeeExempt(issuer.clone());
+    let exempt = env.storage().persistent().has(&key);
+    if exempt {
+        extend_persistent_ttl(env, &key);
+    }
+
+    exempt
+}
+
+/// Mark or unmark an issuer as exempt from issuance fees
+pub fn set_fee_exempt(env: &Env, issuer: &Address, exempt: bool) {
+    let key = DataKey::FeeExempt(issuer.clone());
+    if exempt {
+        env.storage().persistent().set(&key, &true);
+        extend_persistent_ttl(env, &key);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Get the latest version of a certificate template
+pub fn get_template(env: &Env, template_id: &String) -> Option<CertificateTemplate> {
+    let key = DataKey::Template(template_id.clone());
+    let template = env.storage().persistent().get(&key);
+    if template.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    template
+}
+
+/// Store a certificate template as its latest version and keep a snapshot of that version
+pub fn set_template(env: &Env, template: &CertificateTemplate) {
+    let key = DataKey::Template(template.template_id.clone());
+    let version_key = DataKey::TemplateVersion(template.template_id.clone(), template.version);
+    env.storage().persistent().set(&key, template);
+    env.storage().persistent().set(&version_key, template);
+    extend_persistent_ttl(env, &key);
+    extend_persistent_ttl(env, &version_key);
+}
+
+/// Get a certificate template at a given version
+pub fn get_template_version(
+    env: &Env,
+    template_id: &String,
+    version: u32,
+) -> Option<CertificateTemplate> {
+    let key = DataKey::TemplateVersion(template_id.clone(), version);
+    let template = env.storage().persistent().get(&key);
+    if template.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    template
+}
+
+/// Get a credential schema
+pub fn get_schema(env: &Env, schema_id: &String) -> Option<CredentialSchema> {
+    let key = DataKey::Schema(schema_id.clone());
+    let schema = env.storage().persistent().get(&key);
+    if schema.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    schema
+}
+
+/// Store a credential schema
+pub fn set_schema(env: &Env, schema: &CredentialSchema) {
+    let key = DataKey::Schema(schema.schema_id.clone());
+    env.storage().persistent().set(&key, schema);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Get a certificate batch
+pub fn get_batch(env: &Env, batch_id: &String) -> Option<CertificateBatch> {
+    let key = DataKey::Batch(batch_id.clone());
+    let batch = env.storage().persistent().get(&key);
+    if batch.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    batch
+}
+
+/// Store a certificate batch
+pub fn set_batch(env: &Env, batch: &CertificateBatch) {
+    let key = DataKey::Batch(batch.batch_id.clone());
+    env.storage().persistent().set(&key, batch);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Get the revocation details of a batch member
+pub fn get_batch_revocation(
+    env: &Env,
+    batch_id: &String,
+    leaf_hash: &BytesN<32>,
+) -> Option<RevocationRecord> {
+    let key = DataKey::BatchRevocation(batch_id.clone(), leaf_hash.clone());
+    let record = env.storage().persistent().get(&key);
+    if record.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+
+    record
+}
+
+/// Store the revocation details of a batch member
+pub fn set_batch_revocation(
+    env: &Env,
+    batch_id: &String,
+    leaf_hash: &BytesN<32>,
+    record: &RevocationRecord,
+) {
+    let key = DataKey::BatchRevocation(batch_id.clone(), leaf_hash.clone());
+    env.storage().persistent().set(&key, record);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Check if a certification authority exists
+pub fn has_authority(env: &Env, address: &Address) -> bool {
+    if !env.storage().instance().has(&StorageKeys::AUTHORITIES) {
+        return false;
+    }
+
+    Storage::get_authorities(env).contains_key(address.clone())
+}
+
+/// Get a certification authority by address
+pub fn get_authority(env: &Env, address: &Address) -> Option<CertificationAuthority> {
+    if !env.storage().instance().has(&StorageKeys::AUTHORITIES) {
+        return None;
+    }
+
+    Storage::get_authorities(env).get(address.clone())
+}
+
+/// Set a certification authority
+pub fn set_authority(env: &Env, authority: &CertificationAuthority) {
+    let mut authorities = Storage::get_authorities(env);
+    authorities.set(authority.address.clone(), authority.clone());
+    Storage::set_authorities(env, &authorities);
+}
+
+/// Get all certification authority addresses
+pub fn get_all_authority_addresses(env: &Env) -> Vec<Address> {
+    if !env.storage().instance().has(&StorageKeys::AUTHORITIES) {
+        return Vec::new(env);
+    }
+
+    Storage::get_authorities(env).keys()
+}
+
 /// Set the admin address (standalone function)
 pub fn set_admin(env: &Env, admin: &Address) {
     Storage::set_admin(env, admin);
 }
 
+/// Initialize all storage (standalone function)
+pub fn init_all(env: &Env) {
+    Storage::init_all(env);
+}
+
 /// Check if admin is set (standalone function)
 pub fn has_admin(env: &Env) -> bool {
     Storage::has_admin(env)
@@ -0,0 +1,3076 @@
+#![cfg(test)]
+
+use super::*;
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent, Address as _, Events, Ledger},
+    xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, Map, String, Symbol,
+};
+
+/// Helper function to create a test environment and contract client
+fn setup() -> (Env, Address, SuperCertificationContractClient<'static>) {
+    let env = Env::default();
+    let contract_id = env.register(SuperCertificationContract, ());
+    let client = SuperCertificationContractClient::new(&env, &contract_id);
+
+    (env, contract_id, client)
+}
+
+/// Helper function to initialize the contract with an admin registered as an issuing authority
+fn setup_with_issuer() -> (
+    Env,
+    Address,
+    SuperCertificationContractClient<'static>,
+    SigningKey,
+) {
+    let (env, _, client) = setup();
+    let admin = Address::generate(&env);
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_authority(
+        &admin,
+        &admin,
+        &String::from_str(&env, "Test Authority"),
+        &BytesN::from_array(&env, &signing_key.verifying_key().to_bytes()),
+    );
+
+    (env, admin, client, signing_key)
+}
+
+/// Helper function to sign a certificate's signing digest with an ed25519 key
+fn sign_certificate(
+    env: &Env,
+    cert_id: &String,
+    metadata: &CertificateMetadata,
+    signing_key: &SigningKey,
+) -> BytesN<64> {
+    sign_digest(
+        env,
+        &utils::signing_digest(env, cert_id, metadata, false),
+        signing_key,
+    )
+}
+
+/// Helper function to sign the metadata the contract builds for an issuance
+fn sign_issuance(
+    env: &Env,
+    cert_id: &String,
+    description: &String,
+    cert_type: CertificateType,
+    expiration_date: u64,
+    custom_fields: &Map<String, String>,
+    signing_key: &SigningKey,
+) -> BytesN<64> {
+    let metadata = CertificateMetadata {
+        title: cert_id.clone(),
+        description: description.clone(),
+        issue_date: 0,
+        expiration_date,
+        cert_type,
+        custom_fields: custom_fields.clone(),
+    };
+    sign_certificate(env, cert_id, &metadata, signing_key)
+}
+
+/// Helper function to sign a digest with an ed25519 key
+fn sign_digest(env: &Env, digest: &BytesN<32>, signing_key: &SigningKey) -> BytesN<64> {
+    BytesN::from_array(env, &signing_key.sign(&digest.to_array()).to_bytes())
+}
+
+/// Helper function to issue a certificate signed with the given key through the contract
+fn issue_signed_certificate(
+    env: &Env,
+    client: &SuperCertificationContractClient,
+    issuer: &Address,
+    signing_key: &SigningKey,
+    cert_id: &String,
+    expiration_date: u64,
+) -> CertificateDetails {
+    let metadata = CertificateMetadata {
+        title: cert_id.clone(),
+        description: String::from_str(env, "Milestone 1 delivered"),
+        issue_date: env.ledger().timestamp(),
+        expiration_date,
+        cert_type: CertificateType::Standard,
+        custom_fields: Map::new(env),
+    };
+    let signature = sign_certificate(env, cert_id, &metadata, signing_key);
+
+    client.issue_certificate(
+        issuer,
+        cert_id,
+        &Address::generate(env),
+        &metadata.description,
+        &signature,
+        &metadata.cert_type,
+        &expiration_date,
+        &Map::new(env),
+    );
+
+    client.get_certificate_details(cert_id)
+}
+
+/// Helper function to build certificate metadata for hashing tests
+fn sample_metadata(env: &Env) -> CertificateMetadata {
+    CertificateMetadata {
+        title: String::from_str(env, "Grant Receipt"),
+        description: String::from_str(env, "Milestone 1 delivered"),
+        issue_date: 1_700_000_000,
+        expiration_date: 0,
+        cert_type: CertificateType::Standard,
+        custom_fields: Map::new(env),
+    }
+}
+
+#[test]
+/// Test the metadata hash is the SHA-256 of the XDR encoded (cert_id, metadata) tuple
+fn test_hash_metadata_is_canonical() {
+    let env = Env::default();
+    let cert_id = String::from_str(&env, "cert-123");
+    let metadata = sample_metadata(&env);
+
+    // Recompute the digest the way an off-chain verifier would
+    let payload = (cert_id.clone(), metadata.clone()).to_xdr(&env);
+    let expected = env.crypto().sha256(&payload).to_bytes();
+
+    assert_eq!(utils::hash_metadata(&env, &cert_id, &metadata), expected);
+    assert_ne!(expected, BytesN::from_array(&env, &[0; 32]));
+}
+
+#[test]
+/// Test the metadata hash changes with every metadata field and the certificate ID
+fn test_hash_metadata_covers_all_fields() {
+    let env = Env::default();
+    let cert_id = String::from_str(&env, "cert-123");
+    let metadata = sample_metadata(&env);
+    let base = utils::hash_metadata(&env, &cert_id, &metadata);
+
+    // A different certificate ID yields a different hash
+    let other_id = String::from_str(&env, "cert-124");
+    assert_ne!(utils::hash_metadata(&env, &other_id, &metadata), base);
+
+    // Changing the expiration date yields a different hash
+    let mut changed = metadata.clone();
+    changed.expiration_date = 1_800_000_000;
+    assert_ne!(utils::hash_metadata(&env, &cert_id, &changed), base);
+
+    // Changing the certificate type yields a different hash
+    let mut changed = metadata.clone();
+    changed.cert_type = CertificateType::Academic;
+    assert_ne!(utils::hash_metadata(&env, &cert_id, &changed), base);
+
+    // Adding a custom field yields a different hash
+    let mut changed = metadata.clone();
+    changed
+        .custom_fields
+        .set(String::from_str(&env, "grade"), String::from_str(&env, "A"));
+    assert_ne!(utils::hash_metadata(&env, &cert_id, &changed), base);
+}
+
+#[test]
+/// Test custom fields are hashed independently of their insertion order
+fn test_hash_metadata_custom_fields_order() {
+    let env = Env::default();
+    let cert_id = String::from_str(&env, "cert-123");
+    let grade = String::from_str(&env, "grade");
+    let cohort = String::from_str(&env, "cohort");
+
+    let mut first = sample_metadata(&env);
+    first
+        .custom_fields
+        .set(grade.clone(), String::from_str(&env, "A"));
+    first
+        .custom_fields
+        .set(cohort.clone(), String::from_str(&env, "2024"));
+
+    let mut second = sample_metadata(&env);
+    second
+        .custom_fields
+        .set(cohort, String::from_str(&env, "2024"));
+    second.custom_fields.set(grade, String::from_str(&env, "A"));
+
+    assert_eq!(
+        utils::hash_metadata(&env, &cert_id, &first),
+        utils::hash_metadata(&env, &cert_id, &second)
+    );
+}
+
+#[test]
+/// Test hashes are rendered as lowercase hex strings
+fn test_bytes_to_string_hex() {
+    let env = Env::default();
+
+    let mut raw = [0u8; 32];
+    raw[0] = 0xab;
+    raw[1] = 0x01;
+    raw[31] = 0xf0;
+    let hex = utils::bytes_to_string(&env, &BytesN::from_array(&env, &raw));
+
+    assert_eq!(
+        hex,
+        String::from_str(
+            &env,
+            "ab010000000000000000000000000000000000000000000000000000000000f0"
+        )
+    );
+}
+
+#[test]
+/// Test verification of a genuine certificate
+fn test_verify_certificate() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-123");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert!(result.is_valid);
+    assert!(result.hash_valid);
+    assert!(result.signature_checked_on_write);
+    assert_eq!(result.status, CertificateStatus::Active);
+    assert_eq!(result.owner, certificate.owner);
+    assert_eq!(result.issuer, issuer);
+
+    // Verify with an incorrect metadata hash
+    let result = client.verify_certificate(&cert_id, &String::from_str(&env, "wrong-hash"));
+    assert!(!result.hash_valid);
+
+    // Certificates of a deactivated authority are no longer valid
+    client.deactivate_authority(&issuer, &issuer);
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert!(!result.is_valid && result.signature_checked_on_write);
+    assert_eq!(result.status, CertificateStatus::Active);
+}
+
+#[test]
+/// Test a forged signature rejects the issuance or amendment
+fn test_verify_certificate_invalid_signature() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-123");
+    let metadata = CertificateMetadata {
+        title: cert_id.clone(),
+        description: String::from_str(&env, "Milestone 1 delivered"),
+        issue_date: env.ledger().timestamp(),
+        expiration_date: 0,
+        cert_type: CertificateType::Standard,
+        custom_fields: Map::new(&env),
+    };
+
+    // Sign with a key that does not belong to the issuing authority
+    let forger = SigningKey::from_bytes(&[9; 32]);
+    let signature = sign_certificate(&env, &cert_id, &metadata, &forger);
+
+    let result = client.try_issue_certificate(
+        &issuer,
+        &cert_id,
+        &Address::generate(&env),
+        &metadata.description,
+        &signature,
+        &metadata.cert_type,
+        &0,
+        &Map::new(&env),
+    );
+    assert!(result.is_err());
+    assert!(client.try_get_certificate_details(&cert_id).is_err());
+
+    // Amendments are checked the same way
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    let mut metadata = certificate.metadata;
+    metadata.description = String::from_str(&env, "Milestone 2 delivered");
+    let result = client.try_update_certificate_metadata(
+        &issuer,
+        &cert_id,
+        &metadata.description,
+        &sign_certificate(&env, &cert_id, &metadata, &forger),
+    );
+    assert!(result.is_err());
+    assert_eq!(client.get_certificate_details(&cert_id).version, 1);
+}
+
+#[test]
+/// Test rotating an authority's key keeps certificates signed with the old key valid
+fn test_key_rotation_keeps_certificates_valid() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-123");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    let old_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    assert_eq!(certificate.signing_key, Some(old_key.clone()));
+
+    // Rotate the authority's verification key
+    let new_signing_key = SigningKey::from_bytes(&[8; 32]);
+    let new_key = BytesN::from_array(&env, &new_signing_key.verifying_key().to_bytes());
+    client.update_authority(
+        &issuer,
+        &issuer,
+        &String::from_str(&env, "Test Authority"),
+        &new_key,
+        &true,
+    );
+
+    // The certificate still verifies against the key it was issued with
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert!(result.is_valid);
+    assert!(result.signature_checked_on_write);
+    assert_eq!(
+        client.get_certificate_details(&cert_id).signing_key,
+        Some(old_key)
+    );
+
+    // Amendments must be signed with the new key, which is then recorded
+    let mut metadata = certificate.metadata;
+    metadata.description = String::from_str(&env, "Milestone 2 delivered");
+    let result = client.try_update_certificate_metadata(
+        &issuer,
+        &cert_id,
+        &metadata.description,
+        &sign_certificate(&env, &cert_id, &metadata, &signing_key),
+    );
+    assert!(result.is_err());
+    client.update_certificate_metadata(
+        &issuer,
+        &cert_id,
+        &metadata.description,
+        &sign_certificate(&env, &cert_id, &metadata, &new_signing_key),
+    );
+    assert_eq!(
+        client.get_certificate_details(&cert_id).signing_key,
+        Some(new_key)
+    );
+}
+
+#[test]
+/// Test signatures made before the ledger advances still verify, as the
+/// fields the contract sets at issuance are not signed
+fn test_signature_made_before_issuance() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    env.ledger().set_timestamp(1_000);
+    let owner = Address::generate(&env);
+    let description = String::from_str(&env, "Milestone 1 delivered");
+    let metadata = |cert_id: &String, expiration_date: u64| CertificateMetadata {
+        title: cert_id.clone(),
+        description: description.clone(),
+        issue_date: 0,
+        expiration_date,
+        cert_type: CertificateType::Standard,
+        custom_fields: Map::new(&env),
+    };
+    let verifies = |cert_id: &String| {
+        let details = client.get_certificate_details(cert_id);
+        client
+            .verify_certificate(cert_id, &details.metadata_hash)
+            .signature_checked_on_write
+    };
+
+    // The issue date is not signed
+    let cert_id = String::from_str(&env, "direct");
+    let signature = sign_certificate(&env, &cert_id, &metadata(&cert_id, 0), &signing_key);
+    env.ledger().set_timestamp(2_000);
+    client.issue_certificate(
+        &issuer,
+        &cert_id,
+        &owner,
+        &description,
+        &signature,
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    assert_eq!(
+        client.get_certificate_details(&cert_id).metadata.issue_date,
+        2_000
+    );
+    assert!(verifies(&cert_id));
+
+    // Nor is the issue date set by the final approval of a draft
+    let reviewer = Address::generate(&env);
+    client.grant_role(&issuer, &reviewer, &symbol_short!("ISSUER"));
+    client.set_approval_threshold(&issuer, &CertificateType::Standard, &2);
+    let cert_id = String::from_str(&env, "draft");
+    let signature = sign_certificate(&env, &cert_id, &metadata(&cert_id, 0), &signing_key);
+    client.propose_certificate(
+        &issuer,
+        &cert_id,
+        &owner,
+        &description,
+        &signature,
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    env.ledger().set_timestamp(3_000);
+    client.approve_certificate(&reviewer, &cert_id);
+    assert!(verifies(&cert_id));
+    client.set_approval_threshold(&issuer, &CertificateType::Standard, &1);
+
+    // Nor is an expiration date derived from a template's validity
+    let template_id = String::from_str(&env, "course");
+    client.create_template(
+        &issuer,
+        &template_id,
+        &CertificateType::Standard,
+        &String::from_str(&env, "Course"),
+        &description,
+        &3_600,
+        &soroban_sdk::vec![&env],
+    );
+    let cert_id = String::from_str(&env, "templated");
+    let mut signed = metadata(&cert_id, 0);
+    signed.title = String::from_str(&env, "Course");
+    let signature = sign_digest(
+        &env,
+        &utils::signing_digest(&env, &cert_id, &signed, true),
+        &signing_key,
+    );
+    env.ledger().set_timestamp(4_000);
+    client.issue_from_template(
+        &issuer,
+        &template_id,
+        &cert_id,
+        &owner,
+        &Map::new(&env),
+        &signature,
+    );
+    assert_eq!(
+        client
+            .get_certificate_details(&cert_id)
+            .metadata
+            .expiration_date,
+        7_600
+    );
+    assert!(verifies(&cert_id));
+}
+
+#[test]
+/// Test verification of missing, revoked and expired certificates
+fn test_verify_certificate_invalid_states() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+
+    // Missing certificates fail with CertificateNotFound
+    let missing = String::from_str(&env, "missing");
+    let result = client.try_verify_certificate(&missing, &String::from_str(&env, "hash"));
+    assert_eq!(result, Err(Ok(Error::CertificateNotFound)));
+
+    // Revoked certificates are not valid
+    let cert_id = String::from_str(&env, "cert-123");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    client.update_certificate_status(&issuer, &cert_id, &CertificateStatus::Revoked, &None);
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert!(!result.is_valid);
+    assert!(result.signature_checked_on_write);
+
+    // Certificates past their expiration date are not valid
+    let cert_id = String::from_str(&env, "cert-124");
+    let certificate =
+        issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 1_000);
+    env.ledger().set_timestamp(2_000);
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert!(!result.is_valid);
+}
+
+#[test]
+/// Test contract initialization
+fn test_initialize() {
+    let (env, _, client) = setup();
+    let admin = Address::generate(&env);
+
+    // Initialize the contract
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    // Try to initialize again (should fail)
+    let result = client.try_initialize(&admin);
+    assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+}
+
+#[test]
+/// Test registering and querying certification authorities
+fn test_register_authority() {
+    let (env, _, client) = setup();
+    let admin = Address::generate(&env);
+    let authority = Address::generate(&env);
+    let name = String::from_str(&env, "Test Authority");
+    let key = BytesN::from_array(&env, &[1; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_authority(&admin, &authority, &name, &key);
+
+    // Verify the authority was stored
+    let details = client.get_authority(&authority);
+    assert_eq!(details.name, name);
+    assert_eq!(details.address, authority);
+    assert_eq!(details.verification_key, key);
+    assert!(details.is_active);
+    assert_eq!(
+        client.list_authorities(),
+        soroban_sdk::vec![&env, authority.clone()]
+    );
+
+    // Registering the same authority twice fails
+    let result = client.try_register_authority(&admin, &authority, &name, &key);
+    assert_eq!(result, Err(Ok(Error::AuthorityAlreadyExists)));
+
+    // Unknown authorities are not found
+    let result = client.try_get_authority(&Address::generate(&env));
+    assert_eq!(result, Err(Ok(Error::AuthorityNotFound)));
+}
+
+#[test]
+/// Test only authority managers can register authorities
+fn test_unauthorized_register_authority() {
+    let (env, _, client) = setup();
+    let admin = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let result = client.try_register_authority(
+        &stranger,
+        &Address::generate(&env),
+        &String::from_str(&env, "Rogue Authority"),
+        &BytesN::from_array(&env, &[1; 32]),
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+/// Test updating and deactivating certification authorities
+fn test_update_and_deactivate_authority() {
+    let (env, _, client) = setup();
+    let admin = Address::generate(&env);
+    let authority = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.register_authority(
+        &admin,
+        &authority,
+        &String::from_str(&env, "Test Authority"),
+        &BytesN::from_array(&env, &[1; 32]),
+    );
+
+    // Rotate the verification key and rename the authority
+    let new_name = String::from_str(&env, "Renamed Authority");
+    let new_key = BytesN::from_array(&env, &[2; 32]);
+    client.update_authority(&admin, &authority, &new_name, &new_key, &true);
+    let details = client.get_authority(&authority);
+    assert_eq!(details.name, new_name);
+    assert_eq!(details.verification_key, new_key);
+
+    // Deactivate the authority
+    client.deactivate_authority(&admin, &authority);
+    assert!(!client.get_authority(&authority).is_active);
+
+    // Deactivating twice fails
+    let result = client.try_deactivate_authority(&admin, &authority);
+    assert_eq!(result, Err(Ok(Error::AuthorityInactive)));
+
+    // Updating an unknown authority fails
+    let result =
+        client.try_update_authority(&admin, &Address::generate(&env), &new_name, &new_key, &true);
+    assert_eq!(result, Err(Ok(Error::AuthorityNotFound)));
+}
+
+#[test]
+/// Test certificate issuance by an issuer holding the ISSUER role
+fn test_issue_certificate() {
+    let (env, admin, client, _) = setup_with_issuer();
+    let issuer = Address::generate(&env);
+    let signing_key = SigningKey::from_bytes(&[3; 32]);
+
+    // Hand issuance rights to a staff wallet registered as an authority
+    client.grant_role(&admin, &issuer, &symbol_short!("ISSUER"));
+    client.register_authority(
+        &admin,
+        &issuer,
+        &String::from_str(&env, "Staff Wallet"),
+        &BytesN::from_array(&env, &signing_key.verifying_key().to_bytes()),
+    );
+
+    let cert_id = String::from_str(&env, "cert-123");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    assert_eq!(certificate.issuer, issuer);
+    assert_eq!(certificate.status, CertificateStatus::Active);
+    assert_eq!(certificate.version, 1);
+
+    // Issuing the same certificate ID twice fails
+    let result = client.try_issue_certificate(
+        &issuer,
+        &cert_id,
+        &certificate.owner,
+        &certificate.metadata.description,
+        &certificate.signature,
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::CertificateAlreadyExists)));
+}
+
+#[test]
+/// Test addresses without the ISSUER role cannot issue certificates
+fn test_unauthorized_issue_certificate() {
+    let (env, admin, client, _) = setup_with_issuer();
+    let stranger = Address::generate(&env);
+
+    let result = client.try_issue_certificate(
+        &stranger,
+        &String::from_str(&env, "cert-123"),
+        &Address::generate(&env),
+        &String::from_str(&env, "metadata"),
+        &BytesN::from_array(&env, &[0; 64]),
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Without the admin's authorization the call is rejected
+    env.set_auths(&[]);
+    let result = client.try_issue_certificate(
+        &admin,
+        &String::from_str(&env, "cert-123"),
+        &Address::generate(&env),
+        &String::from_str(&env, "metadata"),
+        &BytesN::from_array(&env, &[0; 64]),
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+/// Test issuers must be registered, active certification authorities
+fn test_issue_certificate_requires_active_authority() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let issuer = Address::generate(&env);
+    client.grant_role(&admin, &issuer, &symbol_short!("ISSUER"));
+
+    // An issuer without a registered authority is refused
+    let cert_id = String::from_str(&env, "cert-123");
+    let metadata = String::from_str(&env, "metadata");
+    let signature = BytesN::from_array(&env, &[0; 64]);
+    let owner = Address::generate(&env);
+    let result = client.try_issue_certificate(
+        &issuer,
+        &cert_id,
+        &owner,
+        &metadata,
+        &signature,
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::AuthorityNotFound)));
+
+    // A deactivated authority is refused
+    client.deactivate_authority(&admin, &admin);
+    let result = client.try_issue_certificate(
+        &admin,
+        &cert_id,
+        &owner,
+        &metadata,
+        &signature,
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::AuthorityInactive)));
+
+    // Reactivating the authority allows issuance again
+    client.update_authority(
+        &admin,
+        &admin,
+        &String::from_str(&env, "Test Authority"),
+        &BytesN::from_array(&env, &signing_key.verifying_key().to_bytes()),
+        &true,
+    );
+    issue_signed_certificate(&env, &client, &admin, &signing_key, &cert_id, 0);
+}
+
+#[test]
+/// Test batch issuance reports the certificates that could not be issued
+fn test_batch_issue_certificates() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let owner = Address::generate(&env);
+    let cert_1 = String::from_str(&env, "cert-1");
+    let cert_2 = String::from_str(&env, "cert-2");
+    let metadata = String::from_str(&env, "metadata");
+    let mut cohort = Map::new(&env);
+    cohort.set(
+        String::from_str(&env, "cohort"),
+        String::from_str(&env, "2024-B"),
+    );
+    let signature_1 = sign_issuance(
+        &env,
+        &cert_1,
+        &metadata,
+        CertificateType::Standard,
+        0,
+        &Map::new(&env),
+        &signing_key,
+    );
+    let signature_2 = sign_issuance(
+        &env,
+        &cert_2,
+        &metadata,
+        CertificateType::Membership,
+        0,
+        &cohort,
+        &signing_key,
+    );
+
+    let failed = client.batch_issue_certificates(
+        &admin,
+        &soroban_sdk::vec![&env, cert_1.clone(), cert_2.clone(), cert_1.clone()],
+        &soroban_sdk::vec![&env, owner.clone(), owner.clone(), owner.clone()],
+        &soroban_sdk::vec![&env, metadata.clone(), metadata.clone(), metadata],
+        &soroban_sdk::vec![&env, signature_1.clone(), signature_2, signature_1],
+        &soroban_sdk::vec![
+            &env,
+            CertificateType::Standard,
+            CertificateType::Membership,
+            CertificateType::Standard
+        ],
+        &soroban_sdk::vec![&env, 0u64, 0u64, 0u64],
+        &soroban_sdk::vec![&env, Map::new(&env), cohort.clone(), Map::new(&env)],
+    );
+
+    // The duplicate third entry is reported as failed
+    assert_eq!(failed, soroban_sdk::vec![&env, cert_1.clone()]);
+    assert_eq!(client.get_certificate_details(&cert_1).owner, owner);
+    let second = client.get_certificate_details(&cert_2);
+    assert_eq!(second.metadata.cert_type, CertificateType::Membership);
+    assert_eq!(second.metadata.custom_fields, cohort);
+
+    // A signature that does not verify rejects the whole batch up front
+    let cert_3 = String::from_str(&env, "cert-3");
+    let cert_4 = String::from_str(&env, "cert-4");
+    let metadata = String::from_str(&env, "metadata");
+    let signature_3 = sign_issuance(
+        &env,
+        &cert_3,
+        &metadata,
+        CertificateType::Standard,
+        0,
+        &Map::new(&env),
+        &signing_key,
+    );
+    let result = client.try_batch_issue_certificates(
+        &admin,
+        &soroban_sdk::vec![&env, cert_3.clone(), cert_4.clone()],
+        &soroban_sdk::vec![&env, owner.clone(), owner.clone()],
+        &soroban_sdk::vec![&env, metadata.clone(), metadata],
+        &soroban_sdk::vec![&env, signature_3.clone(), signature_3],
+        &soroban_sdk::vec![&env, CertificateType::Standard, CertificateType::Standard],
+        &soroban_sdk::vec![&env, 0u64, 0u64],
+        &soroban_sdk::vec![&env, Map::new(&env), Map::new(&env)],
+    );
+    assert!(result.is_err());
+    assert_eq!(
+        client.try_get_certificate_details(&cert_3),
+        Err(Ok(Error::CertificateNotFound))
+    );
+    assert_eq!(
+        client.try_get_certificate_details(&cert_4),
+        Err(Ok(Error::CertificateNotFound))
+    );
+}
+
+#[test]
+/// Test granting, revoking and renouncing roles
+fn test_role_management() {
+    let (env, admin, client, _) = setup_with_issuer();
+    let staff = Address::generate(&env);
+    let issuer_role = symbol_short!("ISSUER");
+    let revoker_role = symbol_short!("REVOKER");
+
+    // The admin holds the ADMIN and ISSUER roles after initialization
+    assert!(client.has_role(&admin, &symbol_short!("ADMIN")));
+    assert!(client.has_role(&admin, &issuer_role));
+
+    // Grant roles to a staff wallet
+    client.grant_role(&admin, &staff, &issuer_role);
+    client.grant_role(&admin, &staff, &revoker_role);
+    assert_eq!(
+        client.get_roles(&staff),
+        soroban_sdk::vec![&env, issuer_role.clone(), revoker_role.clone()]
+    );
+
+    // Non-admins cannot grant or revoke roles
+    let result = client.try_grant_role(&staff, &staff, &symbol_short!("ADMIN"));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_revoke_role(&staff, &admin, &issuer_role);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Revoke a role as admin
+    client.revoke_role(&admin, &staff, &issuer_role);
+    assert!(!client.has_role(&staff, &issuer_role));
+
+    // Renounce a role as its holder
+    client.renounce_role(&staff, &revoker_role);
+    assert!(client.get_roles(&staff).is_empty());
+
+    // Renouncing a role that is not held fails
+    let result = client.try_renounce_role(&staff, &revoker_role);
+    assert_eq!(result, Err(Ok(Error::RoleNotFound)));
+}
+
+#[test]
+/// Test each certificate is stored in its own persistent entry
+fn test_certificate_storage_layout() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_1 = String::from_str(&env, "cert-1");
+    let cert_2 = String::from_str(&env, "cert-2");
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_1, 0);
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_2, 0);
+
+    env.as_contract(&client.address, || {
+        let persistent = env.storage().persistent();
+        assert!(persistent.has(&storage::DataKey::Certificate(cert_1.clone())));
+        assert!(persistent.has(&storage::DataKey::Certificate(cert_2.clone())));
+        assert_eq!(
+            persistent.get(&storage::DataKey::CertificateIndex(1)),
+            Some(cert_2.clone())
+        );
+        assert_eq!(storage::Storage::get_certificate_count(&env), 2);
+    });
+
+    // Updating a certificate does not add it to the index again
+    client.suspend_certificate(&issuer, &cert_1, &0, &String::from_str(&env, "Audit"));
+    env.as_contract(&client.address, || {
+        assert_eq!(
+            storage::get_certificate_ids(&env, 0, 10),
+            soroban_sdk::vec![&env, cert_1.clone(), cert_2.clone()]
+        );
+    });
+}
+
+#[test]
+/// Test listing certificates by owner, in full and page by page
+fn test_list_certificates_by_owner() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    let metadata = String::from_str(&env, "metadata");
+    let ids = [
+        String::from_str(&env, "cert-1"),
+        String::from_str(&env, "cert-2"),
+        String::from_str(&env, "cert-3"),
+    ];
+
+    for cert_id in ids.iter() {
+        client.issue_certificate(
+            &issuer,
+            cert_id,
+            &owner,
+            &metadata,
+            &sign_issuance(
+                &env,
+                cert_id,
+                &metadata,
+                CertificateType::Standard,
+                0,
+                &Map::new(&env),
+                &signing_key,
+            ),
+            &CertificateType::Standard,
+            &0,
+            &Map::new(&env),
+        );
+    }
+    let other_id = String::from_str(&env, "cert-other");
+    client.issue_certificate(
+        &issuer,
+        &other_id,
+        &other,
+        &metadata,
+        &sign_issuance(
+            &env,
+            &other_id,
+            &metadata,
+            CertificateType::Standard,
+            0,
+            &Map::new(&env),
+            &signing_key,
+        ),
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+
+    // The full listing only contains the owner's certificates
+    assert_eq!(
+        client.list_certificates_by_owner(&owner),
+        soroban_sdk::vec![&env, ids[0].clone(), ids[1].clone(), ids[2].clone()]
+    );
+
+    // Walk the owner's certificates two at a time
+    let page = client.list_certificates_by_owner_page(&owner, &0, &2);
+    assert_eq!(
+        page.cert_ids,
+        soroban_sdk::vec![&env, ids[0].clone(), ids[1].clone()]
+    );
+    assert_eq!(page.next_cursor, Some(2));
+    assert_eq!(page.total, 3);
+
+    let page = client.list_certificates_by_owner_page(&owner, &2, &2);
+    assert_eq!(page.cert_ids, soroban_sdk::vec![&env, ids[2].clone()]);
+    assert_eq!(page.next_cursor, None);
+
+    // Owners without certificates get an empty page
+    let page = client.list_certificates_by_owner_page(&Address::generate(&env), &0, &10);
+    assert!(page.cert_ids.is_empty());
+    assert_eq!(page.total, 0);
+
+    // A zero limit is rejected
+    let result = client.try_list_certificates_by_owner_page(&owner, &0, &0);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+}
+
+#[test]
+/// Test listing the whole registry page by page
+fn test_list_certificates() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let owner = Address::generate(&env);
+    let metadata = String::from_str(&env, "metadata");
+    let ids = [
+        String::from_str(&env, "cert-1"),
+        String::from_str(&env, "cert-2"),
+        String::from_str(&env, "cert-3"),
+    ];
+
+    for cert_id in ids.iter() {
+        client.issue_certificate(
+            &issuer,
+            cert_id,
+            &owner,
+            &metadata,
+            &sign_issuance(
+                &env,
+                cert_id,
+                &metadata,
+                CertificateType::Standard,
+                0,
+                &Map::new(&env),
+                &signing_key,
+            ),
+            &CertificateType::Standard,
+            &0,
+            &Map::new(&env),
+        );
+    }
+
+    // Walk the registry two certificates at a time, in issuance order
+    let page = client.list_certificates(&issuer, &0, &2);
+    assert_eq!(
+        page.cert_ids,
+        soroban_sdk::vec![&env, ids[0].clone(), ids[1].clone()]
+    );
+    assert_eq!(page.next_cursor, Some(2));
+    assert_eq!(page.total, 3);
+
+    let page = client.list_certificates(&issuer, &2, &2);
+    assert_eq!(page.cert_ids, soroban_sdk::vec![&env, ids[2].clone()]);
+    assert_eq!(page.next_cursor, None);
+
+    // Verifiers can list the registry too
+    let verifier = Address::generate(&env);
+    client.grant_role(&issuer, &verifier, &symbol_short!("VERIFIER"));
+    assert_eq!(client.list_certificates(&verifier, &0, &10).total, 3);
+
+    // Addresses without a listing role are rejected
+    let result = client.try_list_certificates(&owner, &0, &10);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // A zero limit is rejected
+    let result = client.try_list_certificates(&issuer, &0, &0);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+}
+
+#[test]
+/// Test upgrading the contract code and migrating data a page at a time exactly once
+fn test_upgrade_and_migrate() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let outsider = Address::generate(&env);
+
+    // A fresh contract writes data in the current layout, so nothing is pending
+    assert_eq!(client.get_version(), CONTRACT_VERSION);
+    let result = client.try_migrate(&admin, &0, &10);
+    assert_eq!(result, Err(Ok(Error::MigrationNotRequired)));
+
+    // Only the admin can upgrade or migrate
+    let result = client.try_upgrade(&outsider, &BytesN::from_array(&env, &[0; 32]));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_migrate(&outsider, &0, &10);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Upgrade to an uploaded binary, the smallest module the host accepts
+    let mut wasm = [0u8; 40];
+    wasm[..10].copy_from_slice(&[0, b'a', b's', b'm', 1, 0, 0, 0, 0, 30]);
+    wasm[10] = 17;
+    wasm[11..28].copy_from_slice(b"contractenvmetav0");
+    wasm[35] = 22;
+    let wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(soroban_sdk::Bytes::from_slice(&env, &wasm));
+    client.upgrade(&admin, &wasm_hash);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "UPGRADED"),).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (u32, BytesN<32>, Address) = data.into_val(&env);
+    assert_eq!(data, (CONTRACT_VERSION, wasm_hash, admin.clone()));
+
+    // Put this code back in place as the upgraded code
+    env.register_at(&client.address, SuperCertificationContract, ());
+
+    // Simulate data left behind by an earlier release
+    let ids = ["CERT-1", "CERT-2", "CERT-3"].map(|id| String::from_str(&env, id));
+    for cert_id in ids.iter() {
+        issue_signed_certificate(&env, &client, &admin, &signing_key, cert_id, 0);
+    }
+    env.as_contract(&client.address, || {
+        storage::Storage::set_data_version(&env, &(CONTRACT_VERSION - 1));
+    });
+
+    // The migration runs a page at a time, in order
+    let result = client.try_migrate(&admin, &0, &0);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    assert_eq!(client.migrate(&admin, &0, &2), Some(2));
+    let result = client.try_migrate(&admin, &0, &2);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    env.as_contract(&client.address, || {
+        assert_eq!(
+            storage::Storage::get_data_version(&env),
+            CONTRACT_VERSION - 1
+        );
+    });
+
+    // The data version is only advanced after the last page
+    assert_eq!(client.migrate(&admin, &2, &2), None);
+    env.as_contract(&client.address, || {
+        assert_eq!(storage::Storage::get_data_version(&env), CONTRACT_VERSION);
+        assert_eq!(storage::Storage::get_migration_cursor(&env), 0);
+    });
+    let result = client.try_migrate(&admin, &0, &2);
+    assert_eq!(result, Err(Ok(Error::MigrationNotRequired)));
+    for cert_id in ids.iter() {
+        assert!(
+            client
+                .verify_certificate(
+                    cert_id,
+                    &client.get_certificate_details(cert_id).metadata_hash
+                )
+                .is_valid
+        );
+    }
+}
+
+#[test]
+/// Test certificates are kept in live state on write, on read and on demand
+fn test_certificate_ttl() {
+    let (env, issuer, client, key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-ttl");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &key, &cert_id, 0);
+
+    let config = client.get_config();
+    let cert_ttl = || {
+        env.as_contract(&client.address, || {
+            env.storage()
+                .persistent()
+                .get_ttl(&storage::DataKey::Certificate(cert_id.clone()))
+        })
+    };
+
+    // Issuing extends the new entry to the configured TTL
+    assert_eq!(cert_ttl(), config.ttl_extend_to);
+
+    // Reads leave fresh entries alone, but extend entries close to expiry
+    env.ledger().with_mut(|li| li.sequence_number += 10);
+    client.get_certificate_details(&cert_id);
+    assert_eq!(cert_ttl(), config.ttl_extend_to - 10);
+
+    let elapsed = config.ttl_extend_to - config.ttl_threshold + 1;
+    env.ledger().with_mut(|li| li.sequence_number += elapsed);
+    client.verify_certificate(&cert_id, &String::from_str(&env, ""));
+    assert_eq!(cert_ttl(), config.ttl_extend_to);
+
+    // Anyone can extend a certificate to the full TTL
+    env.ledger().with_mut(|li| li.sequence_number += 100);
+    client.extend_certificate_ttl(&cert_id);
+    assert_eq!(cert_ttl(), config.ttl_extend_to);
+
+    // The entries that make up the certificate's record are extended with it
+    client.suspend_certificate(&issuer, &cert_id, &0, &String::from_str(&env, "Audit"));
+    client.revoke_certificate(&issuer, &cert_id, &RevocationReason::Fraud, &None);
+    let record_keys = [
+        storage::DataKey::Revocation(cert_id.clone()),
+        storage::DataKey::SuspensionHistory(cert_id.clone()),
+        storage::DataKey::CertificateVersion(cert_id.clone(), 1),
+        storage::DataKey::OwnerCertificatePosition(cert_id.clone()),
+        storage::DataKey::OwnerCertificate(certificate.owner.clone(), 0),
+    ];
+    env.ledger().with_mut(|li| li.sequence_number += 100);
+    client.extend_certificate_ttl(&cert_id);
+    env.as_contract(&client.address, || {
+        for key in record_keys.iter() {
+            assert_eq!(
+                env.storage().persistent().get_ttl(key),
+                config.ttl_extend_to
+            );
+        }
+    });
+
+    let result = client.try_extend_certificate_ttl(&String::from_str(&env, "missing"));
+    assert_eq!(result, Err(Ok(Error::CertificateNotFound)));
+}
+
+#[test]
+/// Test updating the TTL configuration
+fn test_set_ttl_config() {
+    let (env, admin, client, _) = setup_with_issuer();
+
+    client.set_ttl_config(&admin, &10_000, &50_000);
+    let config = client.get_config();
+    assert_eq!(config.ttl_threshold, 10_000);
+    assert_eq!(config.ttl_extend_to, 50_000);
+
+    // New writes use the updated configuration
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let cert_id = String::from_str(&env, "cert-ttl");
+    issue_signed_certificate(&env, &client, &admin, &key, &cert_id, 0);
+    env.as_contract(&client.address, || {
+        let ttl = env
+            .storage()
+            .persistent()
+            .get_ttl(&storage::DataKey::Certificate(cert_id.clone()));
+        assert_eq!(ttl, 50_000);
+    });
+
+    // Invalid thresholds are rejected
+    let result = client.try_set_ttl_config(&admin, &0, &50_000);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    let result = client.try_set_ttl_config(&admin, &60_000, &50_000);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    let result = client.try_set_ttl_config(&admin, &1_000, &u32::MAX);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+
+    // Only the admin can change the configuration
+    let result = client.try_set_ttl_config(&Address::generate(&env), &1_000, &50_000);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+/// Test expired certificates report their effective status
+fn test_effective_expired_status() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-exp");
+    let certificate =
+        issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 1_000);
+    assert_eq!(certificate.status, CertificateStatus::Active);
+
+    // Past the expiration date the certificate reads as Expired
+    env.ledger().set_timestamp(2_000);
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.status, CertificateStatus::Expired);
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert_eq!(result.status, CertificateStatus::Expired);
+    assert!(!result.is_valid);
+
+    // Expired certificates can neither be amended nor reactivated
+    let signature = BytesN::from_array(&env, &[0; 64]);
+    let result = client.try_update_certificate_metadata(
+        &issuer,
+        &cert_id,
+        &String::from_str(&env, "amended"),
+        &signature,
+    );
+    assert_eq!(result, Err(Ok(Error::CertificateExpired)));
+    let result =
+        client.try_update_certificate_status(&issuer, &cert_id, &CertificateStatus::Active, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidCertificateStatus)));
+}
+
+#[test]
+/// Test sweeping persists expirations and emits status changed events
+fn test_sweep_expired() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let ids = [
+        String::from_str(&env, "cert-1"),
+        String::from_str(&env, "cert-2"),
+        String::from_str(&env, "cert-3"),
+    ];
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &ids[0], 1_000);
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &ids[1], 0);
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &ids[2], 1_000);
+
+    // Nothing has expired yet
+    let page = client.sweep_expired(&0, &10);
+    assert!(page.cert_ids.is_empty());
+
+    // Sweep the registry two certificates at a time once the dates have passed
+    env.ledger().set_timestamp(2_000);
+    env.set_auths(&[]);
+    let page = client.sweep_expired(&0, &2);
+    assert_eq!(page.cert_ids, soroban_sdk::vec![&env, ids[0].clone()]);
+    assert_eq!(page.next_cursor, Some(2));
+
+    // The sweep emits a status changed event for each transition
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "STATUS_C"), ids[0].clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (CertificateStatus, CertificateStatus, Address) = data.into_val(&env);
+    assert_eq!(data.0, CertificateStatus::Active);
+    assert_eq!(data.1, CertificateStatus::Expired);
+
+    let page = client.sweep_expired(&2, &2);
+    assert_eq!(page.cert_ids, soroban_sdk::vec![&env, ids[2].clone()]);
+    assert_eq!(page.next_cursor, None);
+
+    // The transition is persisted, so a second sweep has nothing to do
+    env.as_contract(&client.address, || {
+        let stored = storage::get_certificate(&env, &ids[0]);
+        assert_eq!(stored.status, CertificateStatus::Expired);
+    });
+    let page = client.sweep_expired(&0, &10);
+    assert!(page.cert_ids.is_empty());
+    assert_eq!(page.total, 3);
+}
+
+#[test]
+/// Test time-bound suspensions lift on their own
+fn test_suspend_certificate_until() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "kyc-1");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    let reason = String::from_str(&env, "Pending KYC review");
+
+    // The suspension must end in the future
+    let result = client.try_suspend_certificate(&issuer, &cert_id, &0, &reason);
+    assert!(result.is_ok());
+    let result = client.try_suspend_certificate(&issuer, &cert_id, &2_000, &reason);
+    assert_eq!(result, Err(Ok(Error::CertificateSuspended)));
+    client.reinstate_certificate(&issuer, &cert_id);
+
+    env.ledger().set_timestamp(1_000);
+    let result = client.try_suspend_certificate(&issuer, &cert_id, &1_000, &reason);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+
+    // Suspend until a later time
+    client.suspend_certificate(&issuer, &cert_id, &2_000, &reason);
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.status, CertificateStatus::Suspended);
+    assert_eq!(details.suspended_until, 2_000);
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert!(!result.is_valid);
+
+    // Once the time has passed the certificate is active again
+    env.ledger().set_timestamp(2_000);
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert_eq!(result.status, CertificateStatus::Active);
+    assert!(result.is_valid);
+
+    // The sweep persists the lifted suspension
+    let page = client.sweep_expired(&0, &10);
+    assert_eq!(page.cert_ids, soroban_sdk::vec![&env, cert_id.clone()]);
+    env.as_contract(&client.address, || {
+        let stored = storage::get_certificate(&env, &cert_id);
+        assert_eq!(stored.status, CertificateStatus::Active);
+        assert_eq!(stored.suspended_until, 0);
+    });
+
+    // Both suspensions are kept in the history
+    let history = client.get_suspension_history(&cert_id);
+    assert_eq!(history.len(), 2);
+    let first = history.get(0).unwrap();
+    assert_eq!(first.until, 0);
+    assert_eq!(first.reinstated_by, Some(issuer.clone()));
+    let second = history.get(1).unwrap();
+    assert_eq!(second.reason, reason);
+    assert_eq!(second.suspended_at, 1_000);
+    assert_eq!(second.until, 2_000);
+    assert_eq!(second.reinstated_by, None);
+}
+
+#[test]
+/// Test reinstating and the states suspensions are rejected in
+fn test_reinstate_certificate() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "kyc-1");
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 5_000);
+    let reason = String::from_str(&env, "Pending KYC review");
+
+    // Only suspended certificates can be reinstated
+    let result = client.try_reinstate_certificate(&issuer, &cert_id);
+    assert_eq!(result, Err(Ok(Error::InvalidCertificateStatus)));
+
+    // Only issuers can suspend
+    let outsider = Address::generate(&env);
+    let result = client.try_suspend_certificate(&outsider, &cert_id, &0, &reason);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Reinstating an open-ended suspension makes the certificate active again
+    client.suspend_certificate(&issuer, &cert_id, &0, &reason);
+    env.ledger().set_timestamp(1_000);
+    client.reinstate_certificate(&issuer, &cert_id);
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.status, CertificateStatus::Active);
+    let record = client.get_suspension_history(&cert_id).get(0).unwrap();
+    assert_eq!(record.reinstated_at, 1_000);
+    assert_eq!(record.reinstated_by, Some(issuer.clone()));
+
+    // Expired and revoked certificates cannot be suspended
+    env.ledger().set_timestamp(6_000);
+    let result = client.try_suspend_certificate(&issuer, &cert_id, &0, &reason);
+    assert_eq!(result, Err(Ok(Error::CertificateExpired)));
+
+    let revoked = String::from_str(&env, "kyc-2");
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &revoked, 0);
+    client.update_certificate_status(&issuer, &revoked, &CertificateStatus::Revoked, &None);
+    let result = client.try_suspend_certificate(&issuer, &revoked, &0, &reason);
+    assert_eq!(result, Err(Ok(Error::CertificateRevoked)));
+
+    let missing = String::from_str(&env, "missing");
+    let result = client.try_get_suspension_history(&missing);
+    assert_eq!(result, Err(Ok(Error::CertificateNotFound)));
+}
+
+#[test]
+/// Test revoking a certificate with a reason code
+fn test_revoke_certificate() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-rev");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    let details = Some(String::from_str(&env, "Replaced by cert-rev-2"));
+
+    // Not revoked yet
+    let result = client.try_get_revocation(&cert_id);
+    assert_eq!(result, Err(Ok(Error::InvalidCertificateStatus)));
+
+    // Issuers without the REVOKER role cannot revoke
+    let issuer_only = Address::generate(&env);
+    client.grant_role(&issuer, &issuer_only, &symbol_short!("ISSUER"));
+    let result = client.try_revoke_certificate(
+        &issuer_only,
+        &cert_id,
+        &RevocationReason::Superseded,
+        &details,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_update_certificate_status(
+        &issuer_only,
+        &cert_id,
+        &CertificateStatus::Revoked,
+        &details,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(
+        client.get_certificate_details(&cert_id).status,
+        CertificateStatus::Active
+    );
+
+    // Revokers can
+    let revoker = Address::generate(&env);
+    client.grant_role(&issuer, &revoker, &symbol_short!("REVOKER"));
+    env.ledger().set_timestamp(1_000);
+    client.revoke_certificate(&revoker, &cert_id, &RevocationReason::Superseded, &details);
+
+    // A CERT_REV event carries the reason code
+    let events = env.events().all();
+    let (_, topics, data) = events.get(events.len() - 2).unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "CERT_REV"), cert_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (Address, RevocationReason, Option<String>) = data.into_val(&env);
+    assert_eq!(
+        data,
+        (
+            revoker.clone(),
+            RevocationReason::Superseded,
+            details.clone()
+        )
+    );
+
+    // The revocation is recorded with its reason, time and revoker
+    let record = client.get_revocation(&cert_id);
+    assert_eq!(record.reason, RevocationReason::Superseded);
+    assert_eq!(record.details, details);
+    assert_eq!(record.revoked_at, 1_000);
+    assert_eq!(record.revoked_by, revoker);
+
+    let stored = client.get_certificate_details(&cert_id);
+    assert_eq!(stored.status, CertificateStatus::Revoked);
+    assert_eq!(stored.revocation_reason, details);
+    let result = client.verify_certificate(&cert_id, &certificate.metadata_hash);
+    assert!(!result.is_valid);
+
+    // A certificate can only be revoked once
+    let result = client.try_revoke_certificate(&revoker, &cert_id, &RevocationReason::Fraud, &None);
+    assert_eq!(result, Err(Ok(Error::CertificateRevoked)));
+}
+
+#[test]
+/// Test the certificate lifecycle rejects illegal status transitions
+fn test_certificate_lifecycle_transitions() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-life");
+    issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 5_000);
+    let set_status = |status: CertificateStatus| {
+        client.try_update_certificate_status(&issuer, &cert_id, &status, &None)
+    };
+
+    // Expired can only be reached by the passage of time
+    assert_eq!(
+        set_status(CertificateStatus::Expired),
+        Err(Ok(Error::InvalidCertificateStatus))
+    );
+    assert_eq!(
+        set_status(CertificateStatus::Active),
+        Err(Ok(Error::InvalidCertificateStatus))
+    );
+
+    // Suspending is recorded in the suspension history, without an end date
+    let reason = String::from_str(&env, "Audit");
+    assert!(client
+        .try_update_certificate_status(
+            &issuer,
+            &cert_id,
+            &CertificateStatus::Suspended,
+            &Some(reason.clone())
+        )
+        .is_ok());
+    let history = client.get_suspension_history(&cert_id);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().reason, reason);
+    assert_eq!(history.get(0).unwrap().until, 0);
+
+    // Suspended can only go back to Active
+    assert_eq!(
+        set_status(CertificateStatus::Suspended),
+        Err(Ok(Error::InvalidCertificateStatus))
+    );
+    assert_eq!(
+        set_status(CertificateStatus::Expired),
+        Err(Ok(Error::InvalidCertificateStatus))
+    );
+    assert!(set_status(CertificateStatus::Active).is_ok());
+    assert_eq!(
+        client.get_certificate_details(&cert_id).status,
+        CertificateStatus::Active
+    );
+    let history = client.get_suspension_history(&cert_id);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().reinstated_by, Some(issuer.clone()));
+
+    // Expired certificates can still be revoked
+    env.ledger().set_timestamp(6_000);
+    assert_eq!(
+        set_status(CertificateStatus::Suspended),
+        Err(Ok(Error::InvalidCertificateStatus))
+    );
+    assert!(set_status(CertificateStatus::Revoked).is_ok());
+
+    // Revoked is terminal
+    for status in [
+        CertificateStatus::Active,
+        CertificateStatus::Suspended,
+        CertificateStatus::Expired,
+    ] {
+        assert_eq!(set_status(status), Err(Ok(Error::InvalidCertificateStatus)));
+    }
+    assert_eq!(
+        set_status(CertificateStatus::Revoked),
+        Err(Ok(Error::CertificateRevoked))
+    );
+    assert_eq!(
+        client.get_certificate_details(&cert_id).status,
+        CertificateStatus::Revoked
+    );
+}
+
+#[test]
+/// Test every version of a certificate is kept and can be listed
+fn test_certificate_history() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "cert-hist");
+    let original = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+
+    // Amend the description twice
+    let descriptions = [
+        String::from_str(&env, "Milestone 2 delivered"),
+        String::from_str(&env, "Milestone 3 delivered"),
+    ];
+    for (i, description) in descriptions.iter().enumerate() {
+        env.ledger().set_timestamp(1_000 * (i as u64 + 1));
+        let mut metadata = client.get_certificate_details(&cert_id).metadata;
+        metadata.description = description.clone();
+        let signature = sign_certificate(&env, &cert_id, &metadata, &signing_key);
+        client.update_certificate_metadata(&issuer, &cert_id, description, &signature);
+    }
+
+    // The update event carries the old and new hashes
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let current = client.get_certificate_details(&cert_id);
+    let second = client.get_certificate_version(&cert_id, &2);
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "CERT_UPD"), cert_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (String, String, u32, Address) = data.into_val(&env);
+    assert_eq!(
+        data,
+        (
+            second.metadata_hash.clone(),
+            current.metadata_hash.clone(),
+            3,
+            issuer.clone()
+        )
+    );
+
+    // The original version can still be confirmed
+    let first = client.get_certificate_version(&cert_id, &1);
+    assert_eq!(first.metadata_hash, original.metadata_hash);
+    assert_eq!(first.signature, original.signature);
+    assert_eq!(first.updated_by, issuer);
+    assert_eq!(second.metadata.description, descriptions[0]);
+    assert_eq!(second.updated_at, 1_000);
+    assert_eq!(current.version, 3);
+
+    // Walk the history two versions at a time
+    let page = client.get_certificate_history(&cert_id, &0, &2);
+    assert_eq!(page.total, 3);
+    assert_eq!(page.versions.len(), 2);
+    assert_eq!(page.versions.get(1).unwrap(), second);
+    assert_eq!(page.next_cursor, Some(2));
+    let page = client.get_certificate_history(&cert_id, &2, &2);
+    assert_eq!(page.versions.len(), 1);
+    assert_eq!(
+        page.versions.get(0).unwrap().metadata_hash,
+        current.metadata_hash
+    );
+    assert_eq!(page.next_cursor, None);
+
+    // Unknown versions and certificates are rejected
+    let result = client.try_get_certificate_version(&cert_id, &4);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    let missing = String::from_str(&env, "missing");
+    let result = client.try_get_certificate_history(&missing, &0, &10);
+    assert_eq!(result, Err(Ok(Error::CertificateNotFound)));
+}
+
+#[test]
+/// Test issuing with custom fields and amending them afterwards
+fn test_custom_fields() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "course-1");
+    let grade = String::from_str(&env, "grade");
+    let hours = String::from_str(&env, "course_hours");
+
+    // Issue with a grade
+    let mut fields = Map::new(&env);
+    fields.set(grade.clone(), String::from_str(&env, "A"));
+    let metadata = CertificateMetadata {
+        title: cert_id.clone(),
+        description: String::from_str(&env, "Rust 101"),
+        issue_date: env.ledger().timestamp(),
+        expiration_date: 0,
+        cert_type: CertificateType::Academic,
+        custom_fields: fields.clone(),
+    };
+    let signature = sign_certificate(&env, &cert_id, &metadata, &signing_key);
+    client.issue_certificate(
+        &issuer,
+        &cert_id,
+        &Address::generate(&env),
+        &metadata.description,
+        &signature,
+        &metadata.cert_type,
+        &0,
+        &fields,
+    );
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.metadata.custom_fields, fields);
+    assert!(
+        client
+            .verify_certificate(&cert_id, &details.metadata_hash)
+            .signature_checked_on_write
+    );
+
+    // Adding a field bumps the version and keeps the signature verifiable
+    let mut amended = details.metadata.clone();
+    amended
+        .custom_fields
+        .set(hours.clone(), String::from_str(&env, "40"));
+    let signature = sign_certificate(&env, &cert_id, &amended, &signing_key);
+    client.set_custom_field(
+        &issuer,
+        &cert_id,
+        &hours,
+        &String::from_str(&env, "40"),
+        &signature,
+    );
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.version, 2);
+    assert_eq!(details.metadata.custom_fields, amended.custom_fields);
+    let result = client.verify_certificate(&cert_id, &details.metadata_hash);
+    assert!(result.hash_valid && result.signature_checked_on_write);
+
+    // Removing a field bumps the version again
+    amended.custom_fields.remove(grade.clone());
+    let signature = sign_certificate(&env, &cert_id, &amended, &signing_key);
+    client.remove_custom_field(&issuer, &cert_id, &grade, &signature);
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.version, 3);
+    assert_eq!(details.metadata.custom_fields.len(), 1);
+    assert_eq!(client.get_certificate_history(&cert_id, &0, &10).total, 3);
+
+    // Removing a missing field is rejected
+    let result = client.try_remove_custom_field(&issuer, &cert_id, &grade, &signature);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+}
+
+#[test]
+/// Test custom fields are checked against the configured limits
+fn test_custom_field_limits() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let signature = BytesN::from_array(&env, &[0; 64]);
+    let cert_id = String::from_str(&env, "course-1");
+    let description = String::from_str(&env, "Rust 101");
+
+    client.set_custom_field_limits(&admin, &2, &8, &4);
+    let config = client.get_config();
+    assert_eq!(config.max_custom_fields, 2);
+    assert_eq!(config.max_field_key_length, 8);
+    assert_eq!(config.max_field_value_length, 4);
+
+    let issue = |fields: &Map<String, String>| {
+        client.try_issue_certificate(
+            &admin,
+            &cert_id,
+            &Address::generate(&env),
+            &description,
+            &sign_issuance(
+                &env,
+                &cert_id,
+                &description,
+                CertificateType::Academic,
+                0,
+                fields,
+                &signing_key,
+            ),
+            &CertificateType::Academic,
+            &0,
+            fields,
+        )
+    };
+
+    // Keys and values over the limits are rejected at issuance
+    let mut fields = Map::new(&env);
+    fields.set(
+        String::from_str(&env, "course_hours"),
+        String::from_str(&env, "40"),
+    );
+    assert_eq!(issue(&fields), Err(Ok(Error::InvalidMetadata)));
+
+    let mut fields = Map::new(&env);
+    fields.set(
+        String::from_str(&env, "grade"),
+        String::from_str(&env, "A plus"),
+    );
+    assert_eq!(issue(&fields), Err(Ok(Error::InvalidMetadata)));
+
+    let mut fields = Map::new(&env);
+    fields.set(String::from_str(&env, ""), String::from_str(&env, "A"));
+    assert_eq!(issue(&fields), Err(Ok(Error::InvalidMetadata)));
+
+    // Within the limits issuance succeeds
+    let mut fields = Map::new(&env);
+    fields.set(String::from_str(&env, "grade"), String::from_str(&env, "A"));
+    fields.set(
+        String::from_str(&env, "hours"),
+        String::from_str(&env, "40"),
+    );
+    assert!(issue(&fields).is_ok());
+
+    // A third field goes over the count limit
+    let result = client.try_set_custom_field(
+        &admin,
+        &cert_id,
+        &String::from_str(&env, "cohort"),
+        &String::from_str(&env, "B"),
+        &signature,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+
+    // Only the admin can change the limits
+    let result = client.try_set_custom_field_limits(&Address::generate(&env), &5, &8, &4);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_set_custom_field_limits(&admin, &5, &0, &4);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+}
+
+#[test]
+/// Test owners can only transfer certificates the issuer marked transferable
+fn test_transfer_certificate() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let description = String::from_str(&env, "Gold member");
+    let badges = [
+        String::from_str(&env, "badge-1"),
+        String::from_str(&env, "badge-2"),
+        String::from_str(&env, "badge-3"),
+    ];
+    for cert_id in badges.iter() {
+        client.issue_certificate(
+            &issuer,
+            cert_id,
+            &owner,
+            &description,
+            &sign_issuance(
+                &env,
+                cert_id,
+                &description,
+                CertificateType::Membership,
+                0,
+                &Map::new(&env),
+                &signing_key,
+            ),
+            &CertificateType::Membership,
+            &0,
+            &Map::new(&env),
+        );
+    }
+
+    // Certificates are soulbound by default
+    assert!(!client.get_certificate_details(&badges[0]).transferable);
+    let result = client.try_transfer_certificate(&badges[0], &owner, &buyer);
+    assert_eq!(result, Err(Ok(Error::CertificateNotTransferable)));
+
+    // Once marked transferable, the owner can hand it over
+    client.set_transferable(&issuer, &badges[0], &true);
+    client.transfer_certificate(&badges[0], &owner, &buyer);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "CERT_TRF"), badges[0].clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (Address, Address) = data.into_val(&env);
+    assert_eq!(data, (owner.clone(), buyer.clone()));
+    assert_eq!(client.get_certificate_details(&badges[0]).owner, buyer);
+
+    // Both owner indexes follow the certificate
+    assert_eq!(
+        client.list_certificates_by_owner(&owner),
+        soroban_sdk::vec![&env, badges[2].clone(), badges[1].clone()]
+    );
+    assert_eq!(
+        client.list_certificates_by_owner(&buyer),
+        soroban_sdk::vec![&env, badges[0].clone()]
+    );
+
+    // Only the current owner can transfer
+    let result = client.try_transfer_certificate(&badges[0], &owner, &buyer);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Revoked certificates cannot change hands
+    client.set_transferable(&issuer, &badges[1], &true);
+    client.revoke_certificate(&issuer, &badges[1], &RevocationReason::Fraud, &None);
+    let result = client.try_transfer_certificate(&badges[1], &owner, &buyer);
+    assert_eq!(result, Err(Ok(Error::CertificateRevoked)));
+
+    // Only issuers can change transferability
+    let result = client.try_set_transferable(&owner, &badges[2], &true);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+/// Test issuers can move a soulbound certificate to a holder's new wallet
+fn test_reassign_owner() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "degree-1");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    let old_wallet = certificate.owner;
+    let new_wallet = Address::generate(&env);
+
+    client.reassign_owner(&issuer, &cert_id, &new_wallet);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "OWNR_RSG"), cert_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (Address, Address, Address) = data.into_val(&env);
+    assert_eq!(
+        data,
+        (old_wallet.clone(), new_wallet.clone(), issuer.clone())
+    );
+
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.owner, new_wallet);
+    assert!(!details.transferable);
+    assert_eq!(client.list_certificates_by_owner(&old_wallet).len(), 0);
+    assert_eq!(
+        client.list_certificates_by_owner(&new_wallet),
+        soroban_sdk::vec![&env, cert_id.clone()]
+    );
+
+    // The holder cannot do the same on their own
+    let result = client.try_reassign_owner(&new_wallet, &cert_id, &old_wallet);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_reassign_owner(&issuer, &cert_id, &new_wallet);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+}
+
+#[test]
+/// Test owners can renounce certificates, leaving a tombstone behind
+fn test_renounce_certificate() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "spam-1");
+    let certificate = issue_signed_certificate(&env, &client, &issuer, &signing_key, &cert_id, 0);
+    let owner = certificate.owner;
+
+    client.renounce_certificate(&cert_id);
+    assert_eq!(
+        env.auths()[0].0,
+        owner.clone(),
+        "renouncing requires the owner's authorization"
+    );
+    let events = env.events().all();
+    let (_, topics, data) = events.get(events.len() - 2).unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "CERT_REN"), cert_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: Address = data.into_val(&env);
+    assert_eq!(data, owner);
+
+    // The certificate leaves the owner's wallet but its ID stays taken
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.status, CertificateStatus::Renounced);
+    assert_eq!(client.list_certificates_by_owner(&owner).len(), 0);
+    assert!(
+        !client
+            .verify_certificate(&cert_id, &details.metadata_hash)
+            .is_valid
+    );
+    let result = client.try_issue_certificate(
+        &issuer,
+        &cert_id,
+        &owner,
+        &String::from_str(&env, "Again"),
+        &BytesN::from_array(&env, &[0; 64]),
+        &CertificateType::Standard,
+        &0,
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::CertificateAlreadyExists)));
+
+    // Renounced is terminal
+    let result = client.try_renounce_certificate(&cert_id);
+    assert_eq!(result, Err(Ok(Error::CertificateRenounced)));
+    let result = client.try_revoke_certificate(&issuer, &cert_id, &RevocationReason::Other, &None);
+    assert_eq!(result, Err(Ok(Error::CertificateRenounced)));
+    let result =
+        client.try_update_certificate_status(&issuer, &cert_id, &CertificateStatus::Active, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidCertificateStatus)));
+    let result = client.try_reassign_owner(&issuer, &cert_id, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(Error::CertificateRenounced)));
+
+    // Revoked certificates cannot be renounced either
+    let revoked_id = String::from_str(&env, "spam-2");
+    let revoked = issue_signed_certificate(&env, &client, &issuer, &signing_key, &revoked_id, 0);
+    client.revoke_certificate(&issuer, &revoked_id, &RevocationReason::Fraud, &None);
+    let result = client.try_renounce_certificate(&revoked_id);
+    assert_eq!(result, Err(Ok(Error::CertificateRevoked)));
+    assert_eq!(
+        client.get_certificate_details(&revoked_id).status,
+        CertificateStatus::Revoked
+    );
+    assert_eq!(
+        client.list_certificates_by_owner(&revoked.owner),
+        soroban_sdk::vec![&env, revoked_id.clone()]
+    );
+}
+
+#[test]
+/// Test certificates issued to a claim key go to the claimant the key signed for
+fn test_claim_certificate() {
+    let (env, issuer, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "degree-1");
+    let description = String::from_str(&env, "Rust 101");
+    let claim_secret = SigningKey::from_bytes(&[3; 32]);
+    let claim_key = BytesN::from_array(&env, &claim_secret.verifying_key().to_bytes());
+    let claim_expires_at = env.ledger().timestamp() + 86_400;
+    let issue = |cert_id: &String, claim_expires_at: u64| {
+        client.try_issue_claimable_certificate(
+            &issuer,
+            cert_id,
+            &claim_key,
+            &claim_expires_at,
+            &description,
+            &sign_issuance(
+                &env,
+                cert_id,
+                &description,
+                CertificateType::Academic,
+                0,
+                &Map::new(&env),
+                &signing_key,
+            ),
+            &CertificateType::Academic,
+            &0,
+            &Map::new(&env),
+        )
+    };
+    let sign_claim = |cert_id: &String, claimant: &Address| {
+        sign_digest(
+            &env,
+            &utils::claim_digest(&env, cert_id, claimant),
+            &claim_secret,
+        )
+    };
+
+    // The claim window must end in the future
+    let result = issue(&cert_id, env.ledger().timestamp());
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+
+    // Unclaimed certificates are held by the contract
+    assert!(issue(&cert_id, claim_expires_at).is_ok());
+    assert_eq!(
+        client.get_certificate_details(&cert_id).owner,
+        client.address
+    );
+    assert_eq!(
+        client.list_certificates_by_owner(&client.address),
+        soroban_sdk::vec![&env, cert_id.clone()]
+    );
+    let claim = client.get_pending_claim(&cert_id);
+    assert_eq!(claim.claim_key, claim_key);
+    assert_eq!(claim.expires_at, claim_expires_at);
+
+    // A signature by another key is rejected
+    let alice = Address::generate(&env);
+    let result = client.try_claim_certificate(
+        &cert_id,
+        &alice,
+        &sign_digest(
+            &env,
+            &utils::claim_digest(&env, &cert_id, &alice),
+            &signing_key,
+        ),
+    );
+    assert!(result.is_err());
+
+    // A claim signed for Alice cannot be replayed by someone else
+    let signature = sign_claim(&cert_id, &alice);
+    let mallory = Address::generate(&env);
+    let result = client.try_claim_certificate(&cert_id, &mallory, &signature);
+    assert!(result.is_err());
+    assert_eq!(
+        client.get_certificate_details(&cert_id).owner,
+        client.address
+    );
+
+    // The claim signed for the claimant makes them the owner
+    client.claim_certificate(&cert_id, &alice, &signature);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "CERT_CLM"), cert_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: Address = data.into_val(&env);
+    assert_eq!(data, alice);
+    assert_eq!(client.get_certificate_details(&cert_id).owner, alice);
+    assert_eq!(client.list_certificates_by_owner(&client.address).len(), 0);
+    assert_eq!(
+        client.list_certificates_by_owner(&alice),
+        soroban_sdk::vec![&env, cert_id.clone()]
+    );
+
+    // Claims are single-use
+    let result = client.try_claim_certificate(&cert_id, &alice, &signature);
+    assert_eq!(result, Err(Ok(Error::ClaimNotFound)));
+    let result = client.try_get_pending_claim(&cert_id);
+    assert_eq!(result, Err(Ok(Error::ClaimNotFound)));
+
+    // Revoked and suspended certificates cannot be claimed
+    let revoked_id = String::from_str(&env, "degree-2");
+    assert!(issue(&revoked_id, claim_expires_at).is_ok());
+    client.revoke_certificate(&issuer, &revoked_id, &RevocationReason::Fraud, &None);
+    let result =
+        client.try_claim_certificate(&revoked_id, &alice, &sign_claim(&revoked_id, &alice));
+    assert_eq!(result, Err(Ok(Error::CertificateRevoked)));
+    let suspended_id = String::from_str(&env, "degree-3");
+    assert!(issue(&suspended_id, claim_expires_at).is_ok());
+    client.suspend_certificate(&issuer, &suspended_id, &0, &String::from_str(&env, "Audit"));
+    let result =
+        client.try_claim_certificate(&suspended_id, &alice, &sign_claim(&suspended_id, &alice));
+    assert_eq!(result, Err(Ok(Error::CertificateSuspended)));
+
+    // Claims cannot be made once the window has closed
+    let late_id = String::from_str(&env, "degree-4");
+    assert!(issue(&late_id, claim_expires_at).is_ok());
+    env.ledger().set_timestamp(claim_expires_at + 1);
+    let result = client.try_claim_certificate(&late_id, &alice, &sign_claim(&late_id, &alice));
+    assert_eq!(result, Err(Ok(Error::ClaimExpired)));
+}
+
+#[test]
+/// Test certificate types with an approval threshold need several issuers to sign off
+fn test_multi_issuer_approval() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let reviewer = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.grant_role(&admin, &reviewer, &symbol_short!("ISSUER"));
+    let owner = Address::generate(&env);
+    let cert_id = String::from_str(&env, "degree-1");
+    let description = String::from_str(&env, "BSc Computer Science");
+    let sign = |cert_id: &String| {
+        sign_issuance(
+            &env,
+            cert_id,
+            &description,
+            CertificateType::Academic,
+            0,
+            &Map::new(&env),
+            &signing_key,
+        )
+    };
+
+    // Only the admin sets thresholds
+    let result = client.try_set_approval_threshold(&reviewer, &CertificateType::Academic, &2);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    client.set_approval_threshold(&admin, &CertificateType::Academic, &2);
+    assert_eq!(client.get_approval_threshold(&CertificateType::Academic), 2);
+    assert_eq!(client.get_approval_threshold(&CertificateType::Standard), 1);
+
+    // A single issuer can no longer issue degrees directly
+    let result = client.try_issue_certificate(
+        &admin,
+        &cert_id,
+        &owner,
+        &description,
+        &sign(&cert_id),
+        &CertificateType::Academic,
+        &0,
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::ApprovalRequired)));
+
+    // The proposal counts as the proposer's approval
+    let propose = |cert_id: &String| {
+        client.propose_certificate(
+            &admin,
+            cert_id,
+            &owner,
+            &description,
+            &sign(cert_id),
+            &CertificateType::Academic,
+            &0,
+            &Map::new(&env),
+        )
+    };
+    propose(&cert_id);
+    let draft = client.get_draft(&cert_id);
+    assert_eq!(draft.approvals, soroban_sdk::vec![&env, admin.clone()]);
+    assert_eq!(draft.threshold, 2);
+    assert_eq!(
+        draft.expires_at,
+        env.ledger().timestamp() + client.get_config().approval_window
+    );
+    assert!(client.try_get_certificate_details(&cert_id).is_err());
+
+    // Approvals must come from distinct issuers
+    let result = client.try_approve_certificate(&admin, &cert_id);
+    assert_eq!(result, Err(Ok(Error::AlreadyApproved)));
+    let result = client.try_approve_certificate(&outsider, &cert_id);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // The second approval issues the certificate
+    client.approve_certificate(&reviewer, &cert_id);
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.owner, owner);
+    assert_eq!(details.issuer, admin);
+    assert_eq!(details.status, CertificateStatus::Active);
+    assert_eq!(
+        client.try_get_draft(&cert_id),
+        Err(Ok(Error::DraftNotFound))
+    );
+
+    // Drafts that miss the threshold in time expire and free their ID
+    let late_id = String::from_str(&env, "degree-2");
+    client.set_approval_window(&admin, &3_600);
+    propose(&late_id);
+    env.ledger().set_timestamp(env.ledger().timestamp() + 3_601);
+    let result = client.try_approve_certificate(&reviewer, &late_id);
+    assert_eq!(result, Err(Ok(Error::DraftExpired)));
+    propose(&late_id);
+    client.approve_certificate(&reviewer, &late_id);
+    assert_eq!(client.get_certificate_details(&late_id).owner, owner);
+
+    // Lowering the threshold back to 1 restores direct issuance
+    client.set_approval_threshold(&admin, &CertificateType::Academic, &1);
+    let direct_id = String::from_str(&env, "degree-3");
+    let result = client.try_issue_certificate(
+        &admin,
+        &direct_id,
+        &owner,
+        &description,
+        &sign(&direct_id),
+        &CertificateType::Academic,
+        &0,
+        &Map::new(&env),
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+/// Test issuer quotas cap total, per-period and per-type issuance
+fn test_issuer_quota() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let owner = Address::generate(&env);
+    let description = String::from_str(&env, "Rust 101");
+    let mut issued = 0u32;
+    let mut issue = |cert_type: CertificateType| {
+        issued += 1;
+        let mut cert_id = [b'c', b'-', b'0', b'0'];
+        cert_id[2] += (issued / 10) as u8;
+        cert_id[3] += (issued % 10) as u8;
+        let cert_id = String::from_bytes(&env, &cert_id);
+        client.try_issue_certificate(
+            &admin,
+            &cert_id,
+            &owner,
+            &description,
+            &sign_issuance(
+                &env,
+                &cert_id,
+                &description,
+                cert_type.clone(),
+                0,
+                &Map::new(&env),
+                &signing_key,
+            ),
+            &cert_type,
+            &0,
+            &Map::new(&env),
+        )
+    };
+
+    // Usage is tracked even without a quota
+    assert!(issue(CertificateType::Standard).is_ok());
+    let usage = client.get_issuer_usage(&admin);
+    assert_eq!(usage.total, 1);
+    assert_eq!(usage.by_type.get(CertificateType::Standard), Some(1));
+    assert_eq!(client.get_issuer_quota(&admin), None);
+
+    // A per-period limit needs a period
+    let mut type_limits = Map::new(&env);
+    type_limits.set(CertificateType::Academic, 1);
+    let mut quota = IssuerQuota {
+        total_limit: 5,
+        period_limit: 2,
+        period_length: 0,
+        type_limits,
+    };
+    let result = client.try_set_issuer_quota(&admin, &admin, &quota);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    quota.period_length = 86_400;
+    client.set_issuer_quota(&admin, &admin, &quota);
+    assert_eq!(client.get_issuer_quota(&admin), Some(quota.clone()));
+
+    // Per-type limit
+    assert!(issue(CertificateType::Academic).is_ok());
+    assert_eq!(
+        issue(CertificateType::Academic),
+        Err(Ok(Error::QuotaExceeded))
+    );
+
+    // Per-period limit, including the certificate issued before the quota was set
+    assert_eq!(
+        issue(CertificateType::Standard),
+        Err(Ok(Error::QuotaExceeded))
+    );
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 86_400);
+    assert!(issue(CertificateType::Standard).is_ok());
+    assert!(issue(CertificateType::Standard).is_ok());
+    assert_eq!(
+        issue(CertificateType::Standard),
+        Err(Ok(Error::QuotaExceeded))
+    );
+
+    // Total limit
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 86_400);
+    assert!(issue(CertificateType::Standard).is_ok());
+    assert_eq!(
+        issue(CertificateType::Standard),
+        Err(Ok(Error::QuotaExceeded))
+    );
+    assert_eq!(client.get_issuer_usage(&admin).total, 5);
+
+    // Only the admin manages quotas, and removing one lifts the limits
+    let result = client.try_remove_issuer_quota(&owner, &admin);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    client.remove_issuer_quota(&admin, &admin);
+    assert!(issue(CertificateType::Standard).is_ok());
+}
+
+#[test]
+/// Test issuance fees are paid from the issuer to the treasury in the configured token
+fn test_issuance_fees() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let treasury = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let fee_token = env.register_stellar_asset_contract_v2(token_admin);
+    let token = soroban_sdk::token::TokenClient::new(&env, &fee_token.address());
+    soroban_sdk::token::StellarAssetClient::new(&env, &fee_token.address()).mint(&admin, &250);
+    let owner = Address::generate(&env);
+    let sign = |cert_id: &String, description: &str| {
+        sign_issuance(
+            &env,
+            cert_id,
+            &String::from_str(&env, description),
+            CertificateType::Academic,
+            0,
+            &Map::new(&env),
+            &signing_key,
+        )
+    };
+    let issue = |cert_id: &str, cert_type: CertificateType| {
+        let cert_id = String::from_str(&env, cert_id);
+        let signature = sign_issuance(
+            &env,
+            &cert_id,
+            &String::from_str(&env, "Rust 101"),
+            cert_type.clone(),
+            0,
+            &Map::new(&env),
+            &signing_key,
+        );
+        client.try_issue_certificate(
+            &admin,
+            &cert_id,
+            &owner,
+            &String::from_str(&env, "Rust 101"),
+            &signature,
+            &cert_type,
+            &0,
+            &Map::new(&env),
+        )
+    };
+
+    // Fees are disabled until a token and treasury are configured
+    client.set_issuance_fee(&admin, &CertificateType::Academic, &100);
+    assert_eq!(client.get_issuance_fee(&CertificateType::Academic), 0);
+    assert!(issue("free-1", CertificateType::Academic).is_ok());
+    assert_eq!(token.balance(&admin), 250);
+
+    // A token needs a treasury, and only the admin configures fees
+    let result = client.try_set_fee_config(&admin, &Some(fee_token.address()), &None);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    let result =
+        client.try_set_fee_config(&owner, &Some(fee_token.address()), &Some(treasury.clone()));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_set_issuance_fee(&admin, &CertificateType::Academic, &-1);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    client.set_fee_config(&admin, &Some(fee_token.address()), &Some(treasury.clone()));
+    assert_eq!(client.get_issuance_fee(&CertificateType::Academic), 100);
+
+    // Each issuance moves the fee of its type to the treasury
+    assert!(issue("paid-1", CertificateType::Academic).is_ok());
+    assert!(issue("paid-2", CertificateType::Standard).is_ok());
+    assert_eq!(token.balance(&admin), 150);
+    assert_eq!(token.balance(&treasury), 100);
+
+    // Exempt issuers pay nothing
+    client.set_fee_exempt(&admin, &admin, &true);
+    assert!(client.is_fee_exempt(&admin));
+    assert!(issue("exempt-1", CertificateType::Academic).is_ok());
+    assert_eq!(token.balance(&admin), 150);
+    client.set_fee_exempt(&admin, &admin, &false);
+
+    // Batches charge each certificate and skip the ones the issuer cannot pay for
+    let batch_1 = String::from_str(&env, "batch-1");
+    let batch_2 = String::from_str(&env, "batch-2");
+    let failed = client.batch_issue_certificates(
+        &admin,
+        &soroban_sdk::vec![&env, batch_1.clone(), batch_2.clone()],
+        &soroban_sdk::vec![&env, owner.clone(), owner.clone()],
+        &soroban_sdk::vec![
+            &env,
+            String::from_str(&env, "Rust 101"),
+            String::from_str(&env, "Rust 102")
+        ],
+        &soroban_sdk::vec![&env, sign(&batch_1, "Rust 101"), sign(&batch_2, "Rust 102")],
+        &soroban_sdk::vec![&env, CertificateType::Academic, CertificateType::Academic],
+        &soroban_sdk::vec![&env, 0u64, 0u64],
+        &soroban_sdk::vec![&env, Map::new(&env), Map::new(&env)],
+    );
+    assert_eq!(
+        failed,
+        soroban_sdk::vec![&env, String::from_str(&env, "batch-2")]
+    );
+    assert_eq!(token.balance(&admin), 50);
+    assert_eq!(token.balance(&treasury), 200);
+
+    // Without the funds, nothing is issued or counted
+    let usage = client.get_issuer_usage(&admin);
+    assert_eq!(
+        issue("unpaid-1", CertificateType::Academic),
+        Err(Ok(Error::FeePaymentFailed))
+    );
+    assert!(client
+        .try_get_certificate_details(&String::from_str(&env, "unpaid-1"))
+        .is_err());
+    assert_eq!(client.get_issuer_usage(&admin), usage);
+}
+
+#[test]
+/// Test drafts settle their fee and quota when they are issued, not when they are proposed
+fn test_draft_fees_and_quota() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let reviewer = Address::generate(&env);
+    client.grant_role(&admin, &reviewer, &symbol_short!("ISSUER"));
+    let treasury = Address::generate(&env);
+    let fee_token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = soroban_sdk::token::TokenClient::new(&env, &fee_token.address());
+    soroban_sdk::token::StellarAssetClient::new(&env, &fee_token.address()).mint(&admin, &250);
+    client.set_fee_config(&admin, &Some(fee_token.address()), &Some(treasury.clone()));
+    client.set_issuance_fee(&admin, &CertificateType::Academic, &100);
+    client.set_approval_threshold(&admin, &CertificateType::Academic, &2);
+    client.set_approval_window(&admin, &3_600);
+    let owner = Address::generate(&env);
+    let description = String::from_str(&env, "BSc Computer Science");
+    let propose = |cert_id: &str| {
+        let cert_id = String::from_str(&env, cert_id);
+        let signature = sign_issuance(
+            &env,
+            &cert_id,
+            &description,
+            CertificateType::Academic,
+            0,
+            &Map::new(&env),
+            &signing_key,
+        );
+        client.try_propose_certificate(
+            &admin,
+            &cert_id,
+            &owner,
+            &description,
+            &signature,
+            &CertificateType::Academic,
+            &0,
+            &Map::new(&env),
+        )
+    };
+
+    // A proposal only lets the contract collect the fee later
+    assert!(propose("degree-1").is_ok());
+    assert_eq!(token.balance(&admin), 250);
+    assert_eq!(token.allowance(&admin, &client.address), 100);
+
+    // An expired draft is never charged, and proposing it again does not charge twice
+    env.ledger().set_timestamp(env.ledger().timestamp() + 3_601);
+    assert!(propose("degree-1").is_ok());
+    client.approve_certificate(&reviewer, &String::from_str(&env, "degree-1"));
+    assert_eq!(token.balance(&admin), 150);
+    assert_eq!(token.balance(&treasury), 100);
+
+    // Proposals are checked against the quota, but only issuance counts against it
+    client.set_issuer_quota(
+        &admin,
+        &admin,
+        &IssuerQuota {
+            total_limit: 2,
+            period_limit: 0,
+            period_length: 0,
+            type_limits: Map::new(&env),
+        },
+    );
+    assert!(propose("degree-2").is_ok());
+    assert!(propose("degree-3").is_ok());
+
+    // The quota is counted at issuance, and a draft over it is not charged
+    client.approve_certificate(&reviewer, &String::from_str(&env, "degree-2"));
+    assert_eq!(token.balance(&admin), 50);
+    let result = client.try_approve_certificate(&reviewer, &String::from_str(&env, "degree-3"));
+    assert_eq!(result, Err(Ok(Error::QuotaExceeded)));
+    assert_eq!(token.balance(&admin), 50);
+
+    // A used up quota rejects new proposals
+    assert_eq!(propose("degree-4"), Err(Ok(Error::QuotaExceeded)));
+}
+
+#[test]
+/// Test issuing from versioned templates and deactivating them
+fn test_certificate_templates() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    env.ledger().set_timestamp(1_000);
+    let template_id = String::from_str(&env, "rust-course");
+    let owner = Address::generate(&env);
+    let create = |caller: &Address, pattern: &str| {
+        client.try_create_template(
+            caller,
+            &template_id,
+            &CertificateType::Academic,
+            &String::from_str(&env, "Rust Course"),
+            &String::from_str(&env, pattern),
+            &3_600,
+            &soroban_sdk::vec![&env, String::from_str(&env, "name")],
+        )
+    };
+
+    // Only the admin manages templates, and IDs are unique
+    assert_eq!(
+        create(&owner, "Completed by {name}"),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert!(create(&admin, "Completed by {name}").is_ok());
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "TMPL_ADD"), template_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: Address = data.into_val(&env);
+    assert_eq!(data, admin);
+    assert_eq!(
+        create(&admin, "Completed by {name}"),
+        Err(Ok(Error::TemplateAlreadyExists))
+    );
+    assert_eq!(client.get_template(&template_id).version, 1);
+
+    // Required fields must be given, and the pattern is filled in from them
+    let mut fields = Map::new(&env);
+    let cert_id = String::from_str(&env, "tmpl-1");
+    let result = client.try_issue_from_template(
+        &admin,
+        &template_id,
+        &cert_id,
+        &owner,
+        &fields,
+        &BytesN::from_array(&env, &[0; 64]),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+    fields.set(
+        String::from_str(&env, "name"),
+        String::from_str(&env, "Ada"),
+    );
+    let metadata = CertificateMetadata {
+        title: String::from_str(&env, "Rust Course"),
+        description: String::from_str(&env, "Completed by Ada"),
+        issue_date: 1_000,
+        expiration_date: 4_600,
+        cert_type: CertificateType::Academic,
+        custom_fields: fields.clone(),
+    };
+    let signature = sign_digest(
+        &env,
+        &utils::signing_digest(&env, &cert_id, &metadata, true),
+        &signing_key,
+    );
+    client.issue_from_template(&admin, &template_id, &cert_id, &owner, &fields, &signature);
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.metadata, metadata);
+    assert_eq!(details.template_id, Some(template_id.clone()));
+    assert_eq!(details.template_version, 1);
+    assert!(
+        client
+            .verify_certificate(&cert_id, &details.metadata_hash)
+            .signature_checked_on_write
+    );
+
+    // Updates publish a new version without touching issued certificates
+    client.update_template(
+        &admin,
+        &template_id,
+        &CertificateType::Academic,
+        &String::from_str(&env, "Rust Course"),
+        &String::from_str(&env, "{name} completed the course"),
+        &0,
+        &soroban_sdk::vec![&env, String::from_str(&env, "name")],
+    );
+    assert_eq!(client.get_template(&template_id).version, 2);
+    assert_eq!(
+        client
+            .get_template_version(&template_id, &1)
+            .description_pattern,
+        String::from_str(&env, "Completed by {name}")
+    );
+    assert_eq!(
+        client.try_get_template_version(&template_id, &3),
+        Err(Ok(Error::InvalidParameter))
+    );
+    let cert_id = String::from_str(&env, "tmpl-2");
+    let metadata = CertificateMetadata {
+        description: String::from_str(&env, "Ada completed the course"),
+        expiration_date: 0,
+        ..metadata
+    };
+    let signature = sign_digest(
+        &env,
+        &utils::signing_digest(&env, &cert_id, &metadata, true),
+        &signing_key,
+    );
+    client.issue_from_template(&admin, &template_id, &cert_id, &owner, &fields, &signature);
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(
+        details.metadata.description,
+        String::from_str(&env, "Ada completed the course")
+    );
+    assert_eq!(details.metadata.expiration_date, 0);
+    assert_eq!(details.template_version, 2);
+    assert_eq!(
+        client
+            .get_certificate_details(&String::from_str(&env, "tmpl-1"))
+            .template_version,
+        1
+    );
+
+    // Deactivated templates cannot be issued from
+    client.deactivate_template(&admin, &template_id);
+    assert!(!client.get_template(&template_id).is_active);
+    let result = client.try_issue_from_template(
+        &admin,
+        &template_id,
+        &String::from_str(&env, "tmpl-3"),
+        &owner,
+        &fields,
+        &BytesN::from_array(&env, &[0; 64]),
+    );
+    assert_eq!(result, Err(Ok(Error::TemplateInactive)));
+    assert_eq!(
+        client.try_get_template(&String::from_str(&env, "missing")),
+        Err(Ok(Error::TemplateNotFound))
+    );
+}
+
+#[test]
+/// Test values are matched against each schema field type
+fn test_schema_field_types() {
+    let env = Env::default();
+    let matches = |value: &str, field_type: FieldType| {
+        utils::Utils::matches_field_type(&String::from_str(&env, value), &field_type)
+    };
+
+    assert!(matches("anything at all", FieldType::String));
+    assert!(matches("42", FieldType::Integer));
+    assert!(matches("-7", FieldType::Integer));
+    assert!(!matches("-", FieldType::Integer));
+    assert!(!matches("4.2", FieldType::Integer));
+    assert!(matches("2024-02-29", FieldType::Date));
+    assert!(!matches("2023-02-29", FieldType::Date));
+    assert!(!matches("1900-02-29", FieldType::Date));
+    assert!(!matches("2024-13-01", FieldType::Date));
+    assert!(!matches("2024-04-31", FieldType::Date));
+    assert!(!matches("24-04-01", FieldType::Date));
+    assert!(matches("true", FieldType::Bool));
+    assert!(!matches("yes", FieldType::Bool));
+    assert!(matches(
+        "9F86D081884C7D659A2FEAA0C55AD015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+        FieldType::Hash
+    ));
+    assert!(!matches(
+        "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a0",
+        FieldType::Hash
+    ));
+}
+
+#[test]
+/// Test custom fields are validated against the schema bound to their type
+fn test_credential_schemas() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let description = String::from_str(&env, "BSc Computer Science");
+    let schema_id = String::from_str(&env, "degree-v1");
+    let field = |name: &str, field_type: FieldType, required: bool, max_length: u32| SchemaField {
+        name: String::from_str(&env, name),
+        field_type,
+        required,
+        max_length,
+    };
+    let fields = soroban_sdk::vec![
+        &env,
+        field("gpa", FieldType::Integer, true, 3),
+        field("graduated", FieldType::Date, true, 0),
+        field("honors", FieldType::Bool, false, 0)
+    ];
+    let custom_fields = |entries: &[(&str, &str)]| {
+        let mut map = Map::new(&env);
+        for (key, value) in entries {
+            map.set(String::from_str(&env, key), String::from_str(&env, value));
+        }
+        map
+    };
+    let issue = |cert_id: &str, fields: &Map<String, String>| {
+        let cert_id = String::from_str(&env, cert_id);
+        client.try_issue_certificate(
+            &admin,
+            &cert_id,
+            &Address::generate(&env),
+            &description,
+            &sign_issuance(
+                &env,
+                &cert_id,
+                &description,
+                CertificateType::Academic,
+                0,
+                fields,
+                &signing_key,
+            ),
+            &CertificateType::Academic,
+            &0,
+            fields,
+        )
+    };
+    let good = custom_fields(&[("gpa", "385"), ("graduated", "2024-06-30")]);
+
+    // Schema field names must be unique, and schemas cannot be replaced
+    let duplicate = soroban_sdk::vec![
+        &env,
+        field("gpa", FieldType::Integer, true, 0),
+        field("gpa", FieldType::String, false, 0)
+    ];
+    let result = client.try_register_schema(&admin, &schema_id, &duplicate);
+    assert_eq!(result, Err(Ok(Error::InvalidParameter)));
+    client.register_schema(&admin, &schema_id, &fields);
+    assert_eq!(client.get_schema(&schema_id).fields, fields);
+    let result = client.try_register_schema(&admin, &schema_id, &fields);
+    assert_eq!(result, Err(Ok(Error::SchemaAlreadyExists)));
+    let result = client.try_set_type_schema(
+        &admin,
+        &CertificateType::Academic,
+        &Some(String::from_str(&env, "missing")),
+    );
+    assert_eq!(result, Err(Ok(Error::SchemaNotFound)));
+
+    // Certificates issued before the type has a schema are unstructured
+    assert!(issue("before", &Map::new(&env)).is_ok());
+    client.set_type_schema(&admin, &CertificateType::Academic, &Some(schema_id.clone()));
+    assert_eq!(
+        client.get_type_schema(&CertificateType::Academic),
+        Some(schema_id.clone())
+    );
+
+    // Missing, mistyped, too long and unknown fields are rejected
+    for fields in [
+        custom_fields(&[("gpa", "385")]),
+        custom_fields(&[("gpa", "385"), ("graduated", "2024-06-31")]),
+        custom_fields(&[("gpa", "4000"), ("graduated", "2024-06-30")]),
+        custom_fields(&[
+            ("gpa", "385"),
+            ("graduated", "2024-06-30"),
+            ("minor", "Math"),
+        ]),
+    ] {
+        assert_eq!(issue("bad", &fields), Err(Ok(Error::InvalidMetadata)));
+    }
+
+    // The offending field can be looked up with a direct call beforehand
+    let validate =
+        |fields: &Map<String, String>| client.validate_fields(&CertificateType::Academic, fields);
+    assert_eq!(
+        validate(&custom_fields(&[("gpa", "385")])),
+        Some(String::from_str(&env, "graduated"))
+    );
+    assert_eq!(
+        validate(&custom_fields(&[
+            ("gpa", "4000"),
+            ("graduated", "2024-06-30")
+        ])),
+        Some(String::from_str(&env, "gpa"))
+    );
+    assert_eq!(validate(&good), None);
+    assert_eq!(
+        client.validate_fields(
+            &CertificateType::Standard,
+            &custom_fields(&[("gpa", "high")])
+        ),
+        None
+    );
+
+    // Private certificates hide their fields, so they cannot meet required ones
+    let result = client.try_issue_private_certificate(
+        &admin,
+        &String::from_str(&env, "private"),
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[1; 32]),
+        &BytesN::from_array(&env, &[0; 64]),
+        &CertificateType::Academic,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::OperationNotSupported)));
+
+    // Batch issuance keeps going, so the offending field is reported in an event
+    let failed = client.batch_issue_certificates(
+        &admin,
+        &soroban_sdk::vec![&env, String::from_str(&env, "batch-1")],
+        &soroban_sdk::vec![&env, Address::generate(&env)],
+        &soroban_sdk::vec![&env, String::from_str(&env, "BSc")],
+        &soroban_sdk::vec![
+            &env,
+            sign_issuance(
+                &env,
+                &String::from_str(&env, "batch-1"),
+                &String::from_str(&env, "BSc"),
+                CertificateType::Academic,
+                0,
+                &custom_fields(&[("gpa", "high")]),
+                &signing_key,
+            )
+        ],
+        &soroban_sdk::vec![&env, CertificateType::Academic],
+        &soroban_sdk::vec![&env, 0u64],
+        &soroban_sdk::vec![&env, custom_fields(&[("gpa", "high")])],
+    );
+    assert_eq!(failed.len(), 1);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> = (
+        Symbol::new(&env, "SCH_FAIL"),
+        String::from_str(&env, "batch-1"),
+    )
+        .into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (String, String) = data.into_val(&env);
+    assert_eq!(data, (schema_id.clone(), String::from_str(&env, "gpa")));
+
+    // Conforming certificates record the schema and are held to it on update
+    assert!(issue("good", &good).is_ok());
+    let cert_id = String::from_str(&env, "good");
+    assert_eq!(
+        client.get_certificate_details(&cert_id).schema_id,
+        Some(schema_id.clone())
+    );
+    let signature = BytesN::from_array(&env, &[0; 64]);
+    let mut metadata = client.get_certificate_details(&cert_id).metadata;
+    metadata.custom_fields.set(
+        String::from_str(&env, "honors"),
+        String::from_str(&env, "true"),
+    );
+    client.set_custom_field(
+        &admin,
+        &cert_id,
+        &String::from_str(&env, "honors"),
+        &String::from_str(&env, "true"),
+        &sign_certificate(&env, &cert_id, &metadata, &signing_key),
+    );
+    let result = client.try_set_custom_field(
+        &admin,
+        &cert_id,
+        &String::from_str(&env, "honors"),
+        &String::from_str(&env, "summa"),
+        &signature,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+    let result = client.try_remove_custom_field(
+        &admin,
+        &cert_id,
+        &String::from_str(&env, "gpa"),
+        &signature,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+    metadata
+        .custom_fields
+        .remove(String::from_str(&env, "honors"));
+    client.remove_custom_field(
+        &admin,
+        &cert_id,
+        &String::from_str(&env, "honors"),
+        &sign_certificate(&env, &cert_id, &metadata, &signing_key),
+    );
+
+    // Unbinding the schema leaves issued certificates bound to it
+    client.set_type_schema(&admin, &CertificateType::Academic, &None);
+    assert!(issue("after", &Map::new(&env)).is_ok());
+    let result = client.try_remove_custom_field(
+        &admin,
+        &cert_id,
+        &String::from_str(&env, "gpa"),
+        &signature,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidMetadata)));
+    assert_eq!(
+        client
+            .get_certificate_details(&String::from_str(&env, "before"))
+            .schema_id,
+        None
+    );
+}
+
+#[test]
+/// Test fields of a private certificate are disclosed one at a time
+fn test_selective_disclosure() {
+    let (env, admin, client, signing_key) = setup_with_issuer();
+    let cert_id = String::from_str(&env, "kyc-1");
+    let owner = Address::generate(&env);
+    let field = |name: &str, value: &str, salt: u8| {
+        let salt = BytesN::from_array(&env, &[salt; 32]);
+        let name = String::from_str(&env, name);
+        let value = String::from_str(&env, value);
+        let leaf = utils::Utils::disclosure_leaf(&env, &name, &value, &salt);
+        (name, value, salt, leaf)
+    };
+
+    // Three leaves; the last one is promoted to the second level unpaired
+    let name = field("name", "Ada Lovelace", 1);
+    let degree = field("degree", "BSc", 2);
+    let birth = field("birth_date", "1990-01-01", 3);
+    let inner = utils::Utils::merkle_parent(&env, &name.3, &degree.3);
+    let root = utils::Utils::merkle_parent(&env, &inner, &birth.3);
+
+    let metadata = CertificateMetadata {
+        title: cert_id.clone(),
+        description: utils::bytes_to_string(&env, &root),
+        issue_date: env.ledger().timestamp(),
+        expiration_date: 0,
+        cert_type: CertificateType::Standard,
+        custom_fields: Map::new(&env),
+    };
+    let signature = sign_certificate(&env, &cert_id, &metadata, &signing_key);
+    client.issue_private_certificate(
+        &admin,
+        &cert_id,
+        &owner,
+        &root,
+        &signature,
+        &CertificateType::Standard,
+        &0,
+    );
+
+    // Only the root is stored, and the issuer's signature covers it
+    let details = client.get_certificate_details(&cert_id);
+    assert_eq!(details.fields_root, Some(root.clone()));
+    assert_eq!(details.metadata, metadata);
+    assert!(
+        client
+            .verify_certificate(&cert_id, &details.metadata_hash)
+            .signature_checked_on_write
+    );
+
+    // Each field can be disclosed on its own
+    let disclose = |field: &(String, String, BytesN<32>, BytesN<32>),
+                    value: &String,
+                    proof: soroban_sdk::Vec<BytesN<32>>| {
+        client.verify_field_disclosure(&cert_id, &field.0, value, &field.2, &proof)
+    };
+    assert!(disclose(
+        &degree,
+        &degree.1,
+        soroban_sdk::vec![&env, name.3.clone(), birth.3.clone()]
+    ));
+    assert!(disclose(
+        &birth,
+        &birth.1,
+        soroban_sdk::vec![&env, inner.clone()]
+    ));
+
+    // Wrong values, salts and proofs do not verify
+    assert!(!disclose(
+        &degree,
+        &String::from_str(&env, "MSc"),
+        soroban_sdk::vec![&env, name.3.clone(), birth.3.clone()]
+    ));
+    assert!(!disclose(
+        &(
+            degree.0.clone(),
+            degree.1.clone(),
+            name.2.clone(),
+            degree.3.clone()
+        ),
+        &degree.1,
+        soroban_sdk::vec![&env, name.3.clone(), birth.3.clone()]
+    ));
+    assert!(!disclose(
+        &degree,
+        &degree.1,
+        soroban_sdk::vec![&env, birth.3.clone()]
+    ));
+
+    // Certificates without a fields root have nothing to disclose
+    let public = issue_signed_certificate(
+        &env,
+        &client,
+        &admin,
+        &signing_key,
+        &String::from_str(&env, "public-1"),
+        0,
+    );
+    assert_eq!(public.fields_root, None);
+    let result = client.try_verify_field_disclosure(
+        &String::from_str(&env, "public-1"),
+        &degree.0,
+        &degree.1,
+        &degree.2,
+        &soroban_sdk::vec![&env],
+    );
+    assert_eq!(result, Err(Ok(Error::OperationNotSupported)));
+
+    // The committed fields of a private certificate cannot be amended
+    let result = client.try_update_certificate_metadata(
+        &admin,
+        &cert_id,
+        &String::from_str(&env, "Ada Lovelace, BSc"),
+        &signature,
+    );
+    assert_eq!(result, Err(Ok(Error::PrivateCertificate)));
+    let result = client.try_set_custom_field(&admin, &cert_id, &degree.0, &degree.1, &signature);
+    assert_eq!(result, Err(Ok(Error::PrivateCertificate)));
+    let result = client.try_remove_custom_field(&admin, &cert_id, &degree.0, &signature);
+    assert_eq!(result, Err(Ok(Error::PrivateCertificate)));
+}
+
+#[test]
+/// Test anchoring a batch, verifying its members and revoking them one at a time
+fn test_batch_anchoring() {
+    let (env, admin, client, _) = setup_with_issuer();
+    let batch_id = String::from_str(&env, "devcon-2024");
+    let attendee = |name: &str| {
+        let mut fields = Map::new(&env);
+        fields.set(String::from_str(&env, "name"), String::from_str(&env, name));
+        fields
+    };
+    let members = [attendee("Ada"), attendee("Grace"), attendee("Alan")];
+    let leaves = members
+        .each_ref()
+        .map(|fields| utils::Utils::batch_leaf(&env, &batch_id, fields));
+    let inner = utils::Utils::merkle_parent(&env, &leaves[0], &leaves[1]);
+    let root = utils::Utils::merkle_parent(&env, &inner, &leaves[2]);
+    client.set_issuer_quota(
+        &admin,
+        &admin,
+        &IssuerQuota {
+            total_limit: 4,
+            period_limit: 0,
+            period_length: 0,
+            type_limits: Map::new(&env),
+        },
+    );
+
+    // The batch counts against the quota as one certificate per member
+    let anchor = |batch_id: &String, count: u32| {
+        client.try_anchor_batch(
+            &admin,
+            batch_id,
+            &root,
+            &count,
+            &CertificateType::Membership,
+            &1_000,
+        )
+    };
+    assert_eq!(anchor(&batch_id, 5), Err(Ok(Error::QuotaExceeded)));
+    assert!(anchor(&batch_id, 3).is_ok());
+    assert_eq!(anchor(&batch_id, 1), Err(Ok(Error::BatchAlreadyExists)));
+    assert_eq!(client.get_issuer_usage(&admin).total, 3);
+    assert_eq!(client.get_batch(&batch_id).count, 3);
+
+    // Members prove inclusion with their fields and proof
+    let result = client.verify_batch_membership(
+        &batch_id,
+        &members[1],
+        &soroban_sdk::vec![&env, leaves[0].clone(), leaves[2].clone()],
+    );
+    assert!(result.is_member && result.is_valid);
+    assert_eq!(result.leaf_hash, leaves[1]);
+    let result = client.verify_batch_membership(
+        &batch_id,
+        &attendee("Mallory"),
+        &soroban_sdk::vec![&env, leaves[0].clone(), leaves[2].clone()],
+    );
+    assert!(!result.is_member && !result.is_valid);
+
+    // Revoked members are reported as such, and only revokers can revoke
+    let revoke = |batch_id: &String, leaf_hash: &BytesN<32>, proof: &Vec<BytesN<32>>| {
+        client.try_revoke_batch_member(
+            &admin,
+            batch_id,
+            leaf_hash,
+            proof,
+            &RevocationReason::Fraud,
+            &None,
+        )
+    };
+    let proof = soroban_sdk::vec![&env, inner.clone()];
+    let result = client.try_revoke_batch_member(
+        &Address::generate(&env),
+        &batch_id,
+        &leaves[2],
+        &proof,
+        &RevocationReason::Fraud,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Revocations need a membership proof
+    let outsider = utils::Utils::batch_leaf(&env, &batch_id, &attendee("Mallory"));
+    assert_eq!(
+        revoke(&batch_id, &outsider, &proof),
+        Err(Ok(Error::VerificationFailed))
+    );
+    assert_eq!(client.get_batch(&batch_id).revoked_count, 0);
+    assert!(revoke(&batch_id, &leaves[2], &proof).is_ok());
+    let (_, topics, data) = env.events().all().last().unwrap();
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "BTCH_REV"), batch_id.clone()).into_val(&env);
+    assert_eq!(topics, expected_topics);
+    let data: (BytesN<32>, RevocationReason, Address) = data.into_val(&env);
+    assert_eq!(
+        data,
+        (leaves[2].clone(), RevocationReason::Fraud, admin.clone())
+    );
+    assert_eq!(
+        revoke(&batch_id, &leaves[2], &proof),
+        Err(Ok(Error::CertificateRevoked))
+    );
+    let result = client.verify_batch_membership(&batch_id, &members[2], &proof);
+    assert!(result.is_member && result.is_revoked && !result.is_valid);
+    assert_eq!(client.get_batch(&batch_id).revoked_count, 1);
+
+    // No more members can be revoked than the batch was anchored with
+    let small_id = String::from_str(&env, "devcon-small");
+    let small_leaves = [attendee("Ada"), attendee("Grace")]
+        .each_ref()
+        .map(|fields| utils::Utils::batch_leaf(&env, &small_id, fields));
+    client.anchor_batch(
+        &admin,
+        &small_id,
+        &utils::Utils::merkle_parent(&env, &small_leaves[0], &small_leaves[1]),
+        &1,
+        &CertificateType::Membership,
+        &0,
+    );
+    assert!(revoke(
+        &small_id,
+        &small_leaves[0],
+        &soroban_sdk::vec![&env, small_leaves[1].clone()]
+    )
+    .is_ok());
+    assert_eq!(
+        revoke(
+            &small_id,
+            &small_leaves[1],
+            &soroban_sdk::vec![&env, small_leaves[0].clone()]
+        ),
+        Err(Ok(Error::InvalidParameter))
+    );
+
+    // Members stop being valid when the batch expires
+    env.ledger().set_timestamp(1_001);
+    let result = client.verify_batch_membership(
+        &batch_id,
+        &members[0],
+        &soroban_sdk::vec![&env, leaves[1].clone(), leaves[2].clone()],
+    );
+    assert!(result.is_member && !result.is_valid);
+
+    // Batches cannot be anchored already expired
+    assert_eq!(
+        anchor(&String::from_str(&env, "devcon-late"), 1),
+        Err(Ok(Error::InvalidParameter))
+    );
+    assert_eq!(
+        client.try_get_batch(&String::from_str(&env, "missing")),
+        Err(Ok(Error::BatchNotFound))
+    );
+}
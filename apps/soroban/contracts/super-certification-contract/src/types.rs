@@ -32,6 +32,42 @@ pub enum CertificateStatus {
     Expired = 2,
     /// Certificate is temporarily suspended
     Suspended = 3,
+    /// Certificate was renounced by its owner
+    Renounced = 4,
+}
+
+/// Machine-readable reason for revoking a certificate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum RevocationReason {
+    /// The signing key of the issuer or holder was compromised
+    KeyCompromise = 0,
+    /// The certificate was replaced by a newer one
+    Superseded = 1,
+    /// The certificate was obtained or issued fraudulently
+    Fraud = 2,
+    /// The issuer no longer operates
+    CessationOfOperation = 3,
+    /// Any other reason, described in the revocation details
+    Other = 4,
+}
+
+/// Type of a field in a credential schema
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum FieldType {
+    /// Any text
+    String = 0,
+    /// A base-10 integer, optionally negative
+    Integer = 1,
+    /// A calendar date in `YYYY-MM-DD` form
+    Date = 2,
+    /// `true` or `false`
+    Bool = 3,
+    /// A 32-byte hash as 64 hexadecimal digits
+    Hash = 4,
 }
 
 /// Certification authority information
@@ -86,26 +122,313 @@ pub struct CertificateDetails {
     pub version: u32,
     /// Revocation reason (if revoked)
     pub revocation_reason: Option<String>,
+    /// Timestamp at which a suspension lifts on its own (0 while not suspended
+    /// or when the suspension lasts until reinstatement)
+    pub suspended_until: u64,
+    /// Whether the owner may transfer the certificate (soulbound when false)
+    pub transferable: bool,
+    /// Template the certificate was issued from, if any
+    pub template_id: Option<String>,
+    /// Version of that template (0 when not issued from a template)
+    pub template_version: u32,
+    /// Schema the custom fields must conform to, if any
+    pub schema_id: Option<String>,
+    /// Merkle root over salted fields that are disclosed one at a time, if any
+    pub fields_root: Option<BytesN<32>>,
+    /// Issuer key the signature was verified against when it was written
+    /// (`None` if it was stored before signatures were checked on write)
+    pub signing_key: Option<BytesN<32>>,
     /// Timestamp of last status change
     pub last_updated: u64,
 }
 
+/// A snapshot of a certificate's signed content at a given version
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateVersion {
+    /// Version number, starting at 1 on issuance
+    pub version: u32,
+    /// Metadata at this version
+    pub metadata: CertificateMetadata,
+    /// Hash of the metadata at this version
+    pub metadata_hash: String,
+    /// Signature over the metadata at this version
+    pub signature: BytesN<64>,
+    /// Timestamp at which this version was written
+    pub updated_at: u64,
+    /// Address that wrote this version
+    pub updated_by: Address,
+}
+
+/// A page of certificate versions returned by `get_certificate_history`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateHistoryPage {
+    /// Versions in this page, oldest first
+    pub versions: Vec<CertificateVersion>,
+    /// Cursor to pass as `start` for the next page, if any
+    pub next_cursor: Option<u32>,
+    /// Total number of versions of the certificate
+    pub total: u32,
+}
+
+/// Revocation details of a certificate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevocationRecord {
+    /// Reason code for the revocation
+    pub reason: RevocationReason,
+    /// Optional free-text details
+    pub details: Option<String>,
+    /// Timestamp of the revocation
+    pub revoked_at: u64,
+    /// Address that revoked the certificate
+    pub revoked_by: Address,
+}
+
+/// A single suspension in a certificate's history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuspensionRecord {
+    /// Reason given for the suspension
+    pub reason: String,
+    /// Address that suspended the certificate
+    pub suspended_by: Address,
+    /// Timestamp of the suspension
+    pub suspended_at: u64,
+    /// Timestamp at which the suspension lifts on its own (0 for no limit)
+    pub until: u64,
+    /// Timestamp of an early reinstatement (0 if not reinstated by hand)
+    pub reinstated_at: u64,
+    /// Address that reinstated the certificate, if reinstated by hand
+    pub reinstated_by: Option<Address>,
+}
+
+/// A certificate waiting to be claimed by the holder of a secret claim key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingClaim {
+    /// Ed25519 public key whose secret is handed to the recipient
+    pub claim_key: BytesN<32>,
+    /// Timestamp after which the certificate can no longer be claimed
+    pub expires_at: u64,
+}
+
+/// A reusable blueprint for a kind of certificate
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateTemplate {
+    /// Template identifier
+    pub template_id: String,
+    /// Template version, starting at 1 and bumped on every update
+    pub version: u32,
+    /// Type of the certificates issued from the template
+    pub cert_type: CertificateType,
+    /// Title of the certificates issued from the template
+    pub title: String,
+    /// Description with `{field}` placeholders filled in from the custom fields
+    pub description_pattern: String,
+    /// Validity of issued certificates, in seconds (0 for no expiration)
+    pub default_validity_secs: u64,
+    /// Custom fields every certificate issued from the template must have
+    pub required_custom_fields: Vec<String>,
+    /// Whether certificates can be issued from the template
+    pub is_active: bool,
+    /// Address that wrote this version
+    pub updated_by: Address,
+    /// Timestamp at which this version was written
+    pub updated_at: u64,
+}
+
+/// A field of a credential schema
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SchemaField {
+    /// Custom field name
+    pub name: String,
+    /// Type the value must parse as
+    pub field_type: FieldType,
+    /// Whether every certificate must have the field
+    pub required: bool,
+    /// Maximum length, in bytes, of the value (0 for no limit)
+    pub max_length: u32,
+}
+
+/// The structure custom fields of a certificate must conform to
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CredentialSchema {
+    /// Schema identifier
+    pub schema_id: String,
+    /// Fields allowed by the schema; any other custom field is rejected
+    pub fields: Vec<SchemaField>,
+    /// Address that registered the schema
+    pub created_by: Address,
+    /// Timestamp of the registration
+    pub created_at: u64,
+}
+
+/// A proposed certificate waiting for approval from several issuers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateDraft {
+    /// Certificate recipient
+    pub owner: Address,
+    /// Issuer that proposed the certificate, recorded as its issuer
+    pub proposer: Address,
+    /// Certificate description
+    pub description: String,
+    /// Digital signature of the certificate
+    pub signature: BytesN<64>,
+    /// Certificate type
+    pub cert_type: CertificateType,
+    /// Certificate expiration date (Unix timestamp, 0 for no expiration)
+    pub expiration_date: u64,
+    /// Additional custom fields
+    pub custom_fields: Map<String, String>,
+    /// Number of distinct approvals needed, fixed when the draft is proposed
+    pub threshold: u32,
+    /// Issuers that approved the draft so far, starting with the proposer
+    pub approvals: Vec<Address>,
+    /// Timestamp after which the draft can no longer be approved
+    pub expires_at: u64,
+}
+
+/// Issuance limits of a single issuer, where 0 means unlimited
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssuerQuota {
+    /// Maximum number of certificates the issuer can issue in total
+    pub total_limit: u32,
+    /// Maximum number of certificates the issuer can issue per period
+    pub period_limit: u32,
+    /// Length of a period, in seconds of ledger time
+    pub period_length: u64,
+    /// Maximum number of certificates the issuer can issue by certificate type
+    pub type_limits: Map<CertificateType, u32>,
+}
+
+/// Number of certificates an issuer has issued, tracked against its quota
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssuerUsage {
+    /// Certificates issued in total
+    pub total: u32,
+    /// Start of the current period
+    pub period_start: u64,
+    /// Certificates issued in the current period
+    pub period_count: u32,
+    /// Certificates issued by certificate type
+    pub by_type: Map<CertificateType, u32>,
+}
+
+/// A cohort of certificates anchored as a single Merkle root
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificateBatch {
+    /// Batch identifier
+    pub batch_id: String,
+    /// Issuer that anchored the batch
+    pub issuer: Address,
+    /// Merkle root over the members' leaves
+    pub merkle_root: BytesN<32>,
+    /// Number of members in the batch
+    pub count: u32,
+    /// Type of the members' certificates
+    pub cert_type: CertificateType,
+    /// Expiration date of the members' certificates (Unix timestamp, 0 for no expiration)
+    pub expiration_date: u64,
+    /// Timestamp at which the batch was anchored
+    pub anchored_at: u64,
+    /// Number of members revoked so far
+    pub revoked_count: u32,
+}
+
+/// Result of checking a member of a certificate batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchVerificationResult {
+    /// Leaf hash of the member, as passed to `revoke_batch_member`
+    pub leaf_hash: BytesN<32>,
+    /// Whether the proof leads from the leaf to the batch root
+    pub is_member: bool,
+    /// Whether the member was revoked
+    pub is_revoked: bool,
+    /// Whether the member holds a valid certificate (a member, not revoked and not expired)
+    pub is_valid: bool,
+}
+
 /// Verification result with detailed information
-//// #[contracttype]
+#[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VerificationResult {
-    /// Whether the certificate exists
-    pub exists: bool,
-    /// Whether the certificate is valid (not revoked or expired)
+    /// Whether the certificate is valid (active, with a signature checked on
+    /// write, from an active authority)
     pub is_valid: bool,
     /// Whether the metadata hash matches
     pub hash_valid: bool,
-    /// Whether the signature is valid
-    pub signature_valid: bool,
+    /// Whether the signature was verified against the issuer's key when it was written
+    pub signature_checked_on_write: bool,
     /// Certificate status
-    pub status: Option<CertificateStatus>,
+    pub status: CertificateStatus,
     /// Certificate owner
-    pub owner: Option<Address>,
+    pub owner: Address,
     /// Certificate issuer
-    pub issuer: Option<Address>,
+    pub issuer: Address,
+}
+
+/// A page of certificate IDs returned by paginated listings
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertificatePage {
+    /// Certificate IDs in this page
+    pub cert_ids: Vec<String>,
+    /// Cursor to pass as `start` for the next page, if any
+    pub next_cursor: Option<u32>,
+    /// Total number of certificates in the listing
+    pub total: u32,
+}
+
+/// Approximate number of ledgers closed per day (one every five seconds)
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Contract configuration stored under `StorageKeys::CONFIG`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractConfig {
+    /// Remaining TTL, in ledgers, below which entries are extended on access
+    pub ttl_threshold: u32,
+    /// TTL, in ledgers, that entries are extended to
+    pub ttl_extend_to: u32,
+    /// Maximum number of custom fields per certificate
+    pub max_custom_fields: u32,
+    /// Maximum length, in bytes, of a custom field key
+    pub max_field_key_length: u32,
+    /// Maximum length, in bytes, of a custom field value
+    pub max_field_value_length: u32,
+    /// Time, in seconds, a certificate draft stays open for approvals
+    pub approval_window: u64,
+    /// Token contract issuance fees are paid in, if fees are enabled
+    pub fee_token: Option<Address>,
+    /// Address that receives issuance fees, if fees are enabled
+    pub fee_treasury: Option<Address>,
+    /// Issuance fee, in the token's smallest unit, by certificate type
+    pub issuance_fees: Map<CertificateType, i128>,
+}
+
+impl ContractConfig {
+    /// Build the default configuration, with fees disabled
+    pub fn new(env: &Env) -> Self {
+        Self {
+            ttl_threshold: 30 * DAY_IN_LEDGERS,
+            ttl_extend_to: 120 * DAY_IN_LEDGERS,
+            max_custom_fields: 20,
+            max_field_key_length: 32,
+            max_field_value_length: 256,
+            approval_window: 7 * 24 * 60 * 60,
+            fee_token: None,
+            fee_treasury: None,
+            issuance_fees: Map::new(env),
+        }
+    }
 }
@@ -1,6 +1,27 @@
 use crate::errors::Error;
-use crate::types::{CertificateDetails, CertificateStatus, VerificationResult};
-use soroban_sdk::{Bytes, BytesN, Env, IntoVal, Map, String, Vec};
+use crate::types::{
+    CertificateDetails, CertificateMetadata, CertificateStatus, ContractConfig, CredentialSchema,
+    FieldType, VerificationResult,
+};
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Vec};
+
+/// Maximum number of entries returned by a paginated listing
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum length, in bytes, of a template description pattern and of the rendered description
+pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+/// Maximum length, in bytes, of a schema field value that is not free text
+const MAX_TYPED_VALUE_LENGTH: usize = 64;
+
+/// Maximum number of hashes in a field disclosure proof
+pub const MAX_PROOF_DEPTH: u32 = 32;
+
+/// Domain separation prefix of a Merkle leaf
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Domain separation prefix of an inner Merkle node
+const NODE_PREFIX: u8 = 0x01;
 
 /// Utility functions for the contract
 pub struct Utils;
@@ -23,102 +44,366 @@ impl Utils {
         current_time > cert.metadata.expiration_date
     }
 
-    /// Verify a digital signature
+    /// Get the status a certificate has right now
+    ///
+    /// An Active certificate past its expiration date counts as Expired, and a
+    /// suspension past its `until` time counts as lifted, even if the transition
+    /// has not been persisted by `sweep_expired` yet.
+    pub fn effective_status(env: &Env, cert: &CertificateDetails) -> CertificateStatus {
+        let mut status = cert.status.clone();
+
+        // Time-bound suspensions lift on their own once `suspended_until` passes
+        if status == CertificateStatus::Suspended
+            && cert.suspended_until != 0
+            && Self::get_current_time(env) >= cert.suspended_until
+        {
+            status = CertificateStatus::Active;
+        }
+
+        if status == CertificateStatus::Active && Self::is_certificate_expired(env, cert) {
+            return CertificateStatus::Expired;
+        }
+
+        status
+    }
+
+    /// Check whether a certificate may move from one status to another
+    ///
+    /// Revoked and Renounced are terminal, Expired is only entered through the passage of time
+    /// and can only be left by revocation, and Suspended can only go back to
+    /// Active (or be revoked). Renounced is only entered through
+    /// `renounce_certificate`, and automatic transitions applied by
+    /// `effective_status` do not go through this table.
+    pub fn is_valid_transition(from: &CertificateStatus, to: &CertificateStatus) -> bool {
+        matches!(
+            (from, to),
+            (CertificateStatus::Active, CertificateStatus::Suspended)
+                | (CertificateStatus::Active, CertificateStatus::Revoked)
+                | (CertificateStatus::Suspended, CertificateStatus::Active)
+                | (CertificateStatus::Suspended, CertificateStatus::Revoked)
+                | (CertificateStatus::Expired, CertificateStatus::Revoked)
+        )
+    }
+
+    /// Verify an ed25519 signature over a 32-byte message
+    ///
+    /// Uses the host `ed25519_verify`, which traps the whole invocation on a
+    /// signature that does not verify, so callers only continue with valid ones.
     pub fn verify_signature(
         env: &Env,
         message: &BytesN<32>,
         signature: &BytesN<64>,
         public_key: &BytesN<32>,
+    ) {
+        env.crypto()
+            .ed25519_verify(public_key, &Bytes::from(message.clone()), signature);
+    }
+
+    /// Generate a canonical hash of the certificate metadata
+    ///
+    /// The digest is the SHA-256 of the XDR encoding of the `(cert_id, metadata)`
+    /// tuple. Struct fields and map entries (including `custom_fields`) are
+    /// serialized in sorted key order, so the same digest can be recomputed
+    /// off-chain from the stored certificate.
+    pub fn hash_metadata(
+        env: &Env,
+        cert_id: &String,
+        metadata: &CertificateMetadata,
+    ) -> BytesN<32> {
+        let payload = (cert_id.clone(), metadata.clone()).to_xdr(env);
+        env.crypto().sha256(&payload).to_bytes()
+    }
+
+    /// Hash a selectively disclosable field into a Merkle leaf
+    ///
+    /// The leaf is the SHA-256 of `0x00` followed by the XDR encoding of the
+    /// `(field_name, value, salt)` tuple. The salt keeps low-entropy values,
+    /// such as a birth date, from being guessed from the leaf.
+    pub fn disclosure_leaf(
+        env: &Env,
+        field_name: &String,
+        value: &String,
+        salt: &BytesN<32>,
+    ) -> BytesN<32> {
+        let mut payload = Bytes::from_array(env, &[LEAF_PREFIX]);
+        payload.append(&(field_name.clone(), value.clone(), salt.clone()).to_xdr(env));
+        env.crypto().sha256(&payload).to_bytes()
+    }
+
+    /// Hash a member of a certificate batch into a Merkle leaf
+    ///
+    /// The leaf is the SHA-256 of `0x00` followed by the XDR encoding of the
+    /// `(batch_id, leaf_fields)` tuple. Map entries are encoded in sorted key
+    /// order, so the leaf does not depend on the order fields were added in.
+    pub fn batch_leaf(
+        env: &Env,
+        batch_id: &String,
+        leaf_fields: &Map<String, String>,
+    ) -> BytesN<32> {
+        let mut payload = Bytes::from_array(env, &[LEAF_PREFIX]);
+        payload.append(&(batch_id.clone(), leaf_fields.clone()).to_xdr(env));
+        env.crypto().sha256(&payload).to_bytes()
+    }
+
+    /// Hash two Merkle nodes into their parent
+    ///
+    /// The parent is the SHA-256 of `0x01` followed by the two nodes in
+    /// ascending byte order, so proofs need no left or right markers.
+    pub fn merkle_parent(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+        let (left, right) = if a.to_array() <= b.to_array() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        let mut payload = Bytes::from_array(env, &[NODE_PREFIX]);
+        payload.append(&Bytes::from(left.clone()));
+        payload.append(&Bytes::from(right.clone()));
+        env.crypto().sha256(&payload).to_bytes()
+    }
+
+    /// Check a Merkle proof leads from a leaf to the given root
+    pub fn verify_merkle_proof(
+        env: &Env,
+        leaf: &BytesN<32>,
+        proof: &Vec<BytesN<32>>,
+        root: &BytesN<32>,
     ) -> bool {
-        // Convert BytesN<32> to Bytes for ed25519_verify
-        let message_bytes: Bytes = message.clone().into_val(env);
-        // Try to verify and return true if successful, false otherwise
-        match env
-            .crypto()
-            .ed25519_verify(public_key, &message_bytes, signature)
-        {
-            // This will never happen since ed25519_verify returns (), not Result
-            // But we need to handle all cases for the compiler
-            _ => true,
-        }
-        // In reality, if verification fails, it will panic
-        // So if we reach here, verification succeeded
+        let computed = proof.iter().fold(leaf.clone(), |node, sibling| {
+            Self::merkle_parent(env, &node, &sibling)
+        });
+
+        computed == *root
     }
 
-    /// Generate a hash of the certificate metadata
-    pub fn hash_metadata(env: &Env, cert_id: &String, metadata: &String) -> BytesN<32> {
-        let mut data = Vec::new(env);
-        data.push_back(cert_id.clone());
-        data.push_back(metadata.clone());
+    /// Generate the digest an issuer signs for a certificate
+    ///
+    /// This is `hash_metadata` over the metadata with the fields the contract
+    /// fills in itself cleared: `issue_date`, and `expiration_date` when it is
+    /// derived from a template's validity. Issuers can therefore sign before
+    /// knowing the ledger time the certificate will be issued at.
+    pub fn signing_digest(
+        env: &Env,
+        cert_id: &String,
+        metadata: &CertificateMetadata,
+        expiry_from_template: bool,
+    ) -> BytesN<32> {
+        let mut signed = metadata.clone();
+        signed.issue_date = 0;
+        if expiry_from_template {
+            signed.expiration_date = 0;
+        }
+
+        Self::hash_metadata(env, cert_id, &signed)
+    }
 
-        // For simplicity, we'll just use a hardcoded hash value
-        // In a real implementation, we would properly hash the certificate ID and metadata
-        BytesN::<32>::from_array(env, &[0; 32])
+    /// Generate the digest a claimant signs with the claim key of a certificate
+    ///
+    /// The digest is the SHA-256 of the XDR encoding of the `(cert_id, claimant)`
+    /// tuple, so a claim signature cannot be replayed for another address.
+    pub fn claim_digest(env: &Env, cert_id: &String, claimant: &Address) -> BytesN<32> {
+        let payload = (cert_id.clone(), claimant.clone()).to_xdr(env);
+        env.crypto().sha256(&payload).to_bytes()
     }
 
     /// Check if a certificate is valid
     pub fn is_certificate_valid(env: &Env, cert: &CertificateDetails) -> bool {
-        // Check if the certificate is active
-        if cert.status != CertificateStatus::Active {
+        // Check if the certificate is active, taking expiry and lifted suspensions into account
+        Self::effective_status(env, cert) == CertificateStatus::Active
+    }
+
+    /// Create a verification result
+    pub fn create_verification_result(
+        env: &Env,
+        cert: &CertificateDetails,
+        metadata_hash: &String,
+        signature_checked_on_write: bool,
+        authority_active: bool,
+    ) -> VerificationResult {
+        VerificationResult {
+            is_valid: Self::is_certificate_valid(env, cert)
+                && signature_checked_on_write
+                && authority_active,
+            hash_valid: cert.metadata_hash == *metadata_hash,
+            signature_checked_on_write,
+            status: cert.status.clone(),
+            owner: cert.owner.clone(),
+            issuer: cert.issuer.clone(),
+        }
+    }
+
+    /// Check custom fields against the configured count and length limits
+    pub fn validate_custom_fields(
+        config: &ContractConfig,
+        custom_fields: &Map<String, String>,
+    ) -> Result<(), Error> {
+        if custom_fields.len() > config.max_custom_fields {
+            return Err(Error::InvalidMetadata);
+        }
+
+        for (key, value) in custom_fields.iter() {
+            if key.is_empty()
+                || key.len() > config.max_field_key_length
+                || value.len() > config.max_field_value_length
+            {
+                return Err(Error::InvalidMetadata);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check custom fields against a credential schema
+    ///
+    /// Returns the name of the first field that is missing, unknown to the
+    /// schema, too long or not of its declared type, or `None` if they conform.
+    pub fn schema_violation(
+        schema: &CredentialSchema,
+        custom_fields: &Map<String, String>,
+    ) -> Option<String> {
+        for field in schema.fields.iter() {
+            let conforms = match custom_fields.get(field.name.clone()) {
+                None => !field.required,
+                Some(value) => {
+                    (field.max_length == 0 || value.len() <= field.max_length)
+                        && Self::matches_field_type(&value, &field.field_type)
+                }
+            };
+            if !conforms {
+                return Some(field.name);
+            }
+        }
+
+        custom_fields
+            .keys()
+            .iter()
+            .find(|key| !schema.fields.iter().any(|field| field.name == *key))
+    }
+
+    /// Check a custom field value parses as the given schema field type
+    pub fn matches_field_type(value: &String, field_type: &FieldType) -> bool {
+        if *field_type == FieldType::String {
+            return true;
+        }
+
+        let len = value.len() as usize;
+        if len > MAX_TYPED_VALUE_LENGTH {
             return false;
         }
+        let mut buf = [0u8; MAX_TYPED_VALUE_LENGTH];
+        value.copy_into_slice(&mut buf[..len]);
+        let bytes = &buf[..len];
+
+        match field_type {
+            FieldType::String => true,
+            FieldType::Integer => {
+                let digits = bytes.strip_prefix(b"-").unwrap_or(bytes);
+                !digits.is_empty() && digits.iter().all(u8::is_ascii_digit)
+            }
+            FieldType::Date => Self::is_valid_date(bytes),
+            FieldType::Bool => bytes == b"true" || bytes == b"false",
+            FieldType::Hash => len == 64 && bytes.iter().all(u8::is_ascii_hexdigit),
+        }
+    }
 
-        // Check if the certificate is expired
-        if Self::is_certificate_expired(env, cert) {
+    /// Check a `YYYY-MM-DD` calendar date, including leap years
+    fn is_valid_date(bytes: &[u8]) -> bool {
+        if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
             return false;
         }
 
-        true
+        let number = |digits: &[u8]| {
+            digits.iter().try_fold(0u32, |acc, byte| {
+                byte.is_ascii_digit()
+                    .then(|| acc * 10 + u32::from(byte - b'0'))
+            })
+        };
+        let (Some(year), Some(month), Some(day)) = (
+            number(&bytes[..4]),
+            number(&bytes[5..7]),
+            number(&bytes[8..]),
+        ) else {
+            return false;
+        };
+
+        let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return false,
+        };
+
+        (1..=days_in_month).contains(&day)
     }
 
-    /// Create a verification result
-    pub fn create_verification_result(
+    /// Fill in the `{field}` placeholders of a template description pattern
+    ///
+    /// Fails with `InvalidMetadata` if a placeholder is not closed, names a
+    /// field that is missing from `values`, or the result is too long.
+    pub fn render_pattern(
         env: &Env,
-        cert: Option<&CertificateDetails>,
-        metadata_hash: &String,
-        signature_valid: bool,
-    ) -> VerificationResult {
-        if let Some(certificate) = cert {
-            let is_valid = Self::is_certificate_valid(env, certificate);
-            let hash_valid = certificate.metadata_hash == *metadata_hash;
-
-            VerificationResult {
-                exists: true,
-                is_valid,
-                hash_valid,
-                signature_valid,
-                status: Some(certificate.status.clone()),
-                owner: Some(certificate.owner.clone()),
-                issuer: Some(certificate.issuer.clone()),
-            }
-        } else {
-            VerificationResult {
-                exists: false,
-                is_valid: false,
-                hash_valid: false,
-                signature_valid: false,
-                status: None,
-                owner: None,
-                issuer: None,
+        pattern: &String,
+        values: &Map<String, String>,
+    ) -> Result<String, Error> {
+        let len = pattern.len() as usize;
+        if len > MAX_DESCRIPTION_LENGTH {
+            return Err(Error::InvalidMetadata);
+        }
+
+        let mut src = [0u8; MAX_DESCRIPTION_LENGTH];
+        pattern.copy_into_slice(&mut src[..len]);
+        let mut out = [0u8; MAX_DESCRIPTION_LENGTH];
+        let mut out_len = 0;
+
+        let mut i = 0;
+        while i < len {
+            if src[i] == b'{' {
+                let close = src[i + 1..len]
+                    .iter()
+                    .position(|byte| *byte == b'}')
+                    .ok_or(Error::InvalidMetadata)?
+                    + i
+                    + 1;
+                let key = String::from_bytes(env, &src[i + 1..close]);
+                let value = values.get(key).ok_or(Error::InvalidMetadata)?;
+                let value_len = value.len() as usize;
+                if out_len + value_len > MAX_DESCRIPTION_LENGTH {
+                    return Err(Error::InvalidMetadata);
+                }
+                value.copy_into_slice(&mut out[out_len..out_len + value_len]);
+                out_len += value_len;
+                i = close + 1;
+            } else {
+                if out_len == MAX_DESCRIPTION_LENGTH {
+                    return Err(Error::InvalidMetadata);
+                }
+                out[out_len] = src[i];
+                out_len += 1;
+                i += 1;
             }
         }
+
+        Ok(String::from_bytes(env, &out[..out_len]))
     }
 
-    /// Truncate a string to a maximum length
-    pub fn truncate_string(s: &String, max_length: usize) -> String {
-        // Convert usize to u32 for comparison with String.len()
-        let max_len_u32 = max_length as u32;
+    /// Validate pagination parameters, capping the limit at `MAX_PAGE_SIZE`
+    pub fn page_limit(limit: u32) -> Result<u32, Error> {
+        if limit == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
+        Ok(limit.min(MAX_PAGE_SIZE))
+    }
 
-        if s.len() <= max_len_u32 {
-            s.clone()
+    /// Get the cursor for the page following one that starts at `start`
+    pub fn next_cursor(start: u32, page_len: u32, total: u32) -> Option<u32> {
+        let next = start.saturating_add(page_len);
+        if page_len > 0 && next < total {
+            Some(next)
         } else {
-            // For Soroban String, we need to create a new string with limited length
-            let _env = s.env();
-
-            // For Soroban String, we need to use a different approach for truncation
-            // For simplicity, we'll just return the original string
-            // In a real implementation, we would need to handle truncation properly
-            s.clone()
+            None
         }
     }
 
@@ -138,15 +423,33 @@ impl Utils {
 // Standalone utility functions for direct use in the contract
 
 /// Generate a hash of the certificate metadata
-pub fn hash_metadata(env: &Env, cert_id: &String, metadata: &String) -> BytesN<32> {
+pub fn hash_metadata(env: &Env, cert_id: &String, metadata: &CertificateMetadata) -> BytesN<32> {
     Utils::hash_metadata(env, cert_id, metadata)
 }
 
-/// Convert BytesN<32> to String for storage
-pub fn bytes_to_string(env: &Env, _bytes: &BytesN<32>) -> String {
-    // For simplicity in this implementation, we'll just return a placeholder string
-    // In a real implementation, we would need to properly convert the bytes to a hex string
-    String::from_str(env, "bytes_hash")
+/// Generate the digest an issuer signs for a certificate
+pub fn signing_digest(
+    env: &Env,
+    cert_id: &String,
+    metadata: &CertificateMetadata,
+    expiry_from_template: bool,
+) -> BytesN<32> {
+    Utils::signing_digest(env, cert_id, metadata, expiry_from_template)
+}
+
+/// Generate the digest a claimant signs to claim a certificate
+pub fn claim_digest(env: &Env, cert_id: &String, claimant: &Address) -> BytesN<32> {
+    Utils::claim_digest(env, cert_id, claimant)
+}
+
+/// Convert BytesN<32> to a lowercase hex String for storage
+pub fn bytes_to_string(env: &Env, bytes: &BytesN<32>) -> String {
+    let mut hex = [0u8; 64];
+    for (i, byte) in bytes.to_array().iter().enumerate() {
+        hex[i * 2] = hex_char(byte >> 4);
+        hex[i * 2 + 1] = hex_char(byte & 0x0f);
+    }
+    String::from_bytes(env, &hex)
 }
 
 /// Helper function to convert a nibble to a hex character